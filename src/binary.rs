@@ -0,0 +1,619 @@
+//! Binary representation of `TimeClue`, e.g. for caching parsed time clues.
+//!
+//! The representation starts with a version byte (`VERSION`), followed by the time clue:
+//! * a tag byte per variant, then the variant fields in order
+//! * integers are little-endian: `u32`, `i32`, `usize` as `u64`
+//! * enums without fields are encoded as a single byte
+//! * `Option<T>` is `0` for `None`, `1` followed by `T` for `Some(T)`
+//! * `FixedOffset` is encoded as its offset east of UTC in seconds (`i32`)
+//!
+//! Time clues nested deeper than `MAX_DEPTH` (e.g. an offset of an offset of ...) are rejected.
+//!
+//! Stability: bytes produced by `to_bytes` decode with `from_bytes` as long as `VERSION` is unchanged.
+//! New variants only add new tags, existing tags are never reused.
+//! `VERSION` is bumped on any other change, older versions are rejected.
+
+use crate::parser::{
+    Direction, Edge, Modifier, MonthClue, Period, Quantifier, ShortcutDay, TimeClue, WeekOfMonth,
+    AMPM, HMS, YMD,
+};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use chrono::{FixedOffset, Weekday};
+use core::convert::TryFrom;
+use core::fmt;
+use core::fmt::Formatter;
+
+/// Version of the binary representation.
+pub const VERSION: u8 = 1;
+
+/// Maximum nesting of time clues accepted by `TimeClue::from_bytes`.
+pub const MAX_DEPTH: usize = 16;
+
+// `Display` and `Error` are implemented by hand since `thiserror` requires `std`.
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    UnsupportedVersion(u8),
+    UnexpectedEnd,
+    InvalidTag(u8),
+    InvalidValue,
+    TrailingBytes(usize),
+    TooDeep,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of bytes"),
+            DecodeError::InvalidTag(t) => write!(f, "invalid tag {}", t),
+            DecodeError::InvalidValue => write!(f, "invalid value"),
+            DecodeError::TrailingBytes(n) => write!(f, "{} trailing bytes", n),
+            DecodeError::TooDeep => write!(f, "time clue nested deeper than {}", MAX_DEPTH),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+impl TimeClue {
+    /// Binary representation of this time clue, see the `binary` module.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![VERSION];
+        write_time_clue(&mut bytes, self);
+        bytes
+    }
+
+    /// Time clue from its binary representation produced by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<TimeClue, DecodeError> {
+        let mut reader = Reader { bytes, depth: 0 };
+        match reader.u8()? {
+            VERSION => {}
+            version => return Err(DecodeError::UnsupportedVersion(version)),
+        }
+        let time_clue = reader.time_clue()?;
+        match reader.bytes.len() {
+            0 => Ok(time_clue),
+            n => Err(DecodeError::TrailingBytes(n)),
+        }
+    }
+}
+
+fn write_u32(bytes: &mut Vec<u8>, n: u32) {
+    bytes.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_i32(bytes: &mut Vec<u8>, n: i32) {
+    bytes.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_usize(bytes: &mut Vec<u8>, n: usize) {
+    bytes.extend_from_slice(&(n as u64).to_le_bytes());
+}
+
+fn write_hms(bytes: &mut Vec<u8>, (h, m, s): &HMS) {
+    write_u32(bytes, *h);
+    write_u32(bytes, *m);
+    write_u32(bytes, *s);
+}
+
+fn write_ymd(bytes: &mut Vec<u8>, (y, m, d): &YMD) {
+    write_i32(bytes, *y);
+    write_u32(bytes, *m);
+    write_u32(bytes, *d);
+}
+
+fn write_option<T>(bytes: &mut Vec<u8>, option: &Option<T>, write: fn(&mut Vec<u8>, &T)) {
+    match option {
+        None => bytes.push(0),
+        Some(t) => {
+            bytes.push(1);
+            write(bytes, t);
+        }
+    }
+}
+
+// Byte encoding of enums without fields, shared with the `repr` module.
+
+pub(crate) fn weekday_tag(weekday: &Weekday) -> u8 {
+    weekday.num_days_from_monday() as u8
+}
+
+pub(crate) fn weekday_from_tag(tag: u8) -> Result<Weekday, DecodeError> {
+    match tag {
+        0 => Ok(Weekday::Mon),
+        1 => Ok(Weekday::Tue),
+        2 => Ok(Weekday::Wed),
+        3 => Ok(Weekday::Thu),
+        4 => Ok(Weekday::Fri),
+        5 => Ok(Weekday::Sat),
+        6 => Ok(Weekday::Sun),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn am_or_pm_tag(am_or_pm: &AMPM) -> u8 {
+    match am_or_pm {
+        AMPM::AM => 0,
+        AMPM::PM => 1,
+    }
+}
+
+pub(crate) fn am_or_pm_from_tag(tag: u8) -> Result<AMPM, DecodeError> {
+    match tag {
+        0 => Ok(AMPM::AM),
+        1 => Ok(AMPM::PM),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn quantifier_tag(quantifier: &Quantifier) -> u8 {
+    match quantifier {
+        Quantifier::Min => 0,
+        Quantifier::Hours => 1,
+        Quantifier::Days => 2,
+        Quantifier::Weeks => 3,
+        Quantifier::Months => 4,
+        Quantifier::Decades => 5,
+        Quantifier::Centuries => 6,
+    }
+}
+
+pub(crate) fn quantifier_from_tag(tag: u8) -> Result<Quantifier, DecodeError> {
+    match tag {
+        0 => Ok(Quantifier::Min),
+        1 => Ok(Quantifier::Hours),
+        2 => Ok(Quantifier::Days),
+        3 => Ok(Quantifier::Weeks),
+        4 => Ok(Quantifier::Months),
+        5 => Ok(Quantifier::Decades),
+        6 => Ok(Quantifier::Centuries),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn modifier_tag(modifier: &Modifier) -> u8 {
+    match modifier {
+        Modifier::Last => 0,
+        Modifier::Next => 1,
+    }
+}
+
+pub(crate) fn modifier_from_tag(tag: u8) -> Result<Modifier, DecodeError> {
+    match tag {
+        0 => Ok(Modifier::Last),
+        1 => Ok(Modifier::Next),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn direction_tag(direction: &Direction) -> u8 {
+    match direction {
+        Direction::Before => 0,
+        Direction::After => 1,
+    }
+}
+
+pub(crate) fn direction_from_tag(tag: u8) -> Result<Direction, DecodeError> {
+    match tag {
+        0 => Ok(Direction::Before),
+        1 => Ok(Direction::After),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn shortcut_day_tag(shortcut_day: &ShortcutDay) -> u8 {
+    match shortcut_day {
+        ShortcutDay::Today => 0,
+        ShortcutDay::Yesterday => 1,
+        ShortcutDay::Tomorrow => 2,
+    }
+}
+
+pub(crate) fn shortcut_day_from_tag(tag: u8) -> Result<ShortcutDay, DecodeError> {
+    match tag {
+        0 => Ok(ShortcutDay::Today),
+        1 => Ok(ShortcutDay::Yesterday),
+        2 => Ok(ShortcutDay::Tomorrow),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn edge_tag(edge: &Edge) -> u8 {
+    match edge {
+        Edge::Start => 0,
+        Edge::End => 1,
+    }
+}
+
+pub(crate) fn edge_from_tag(tag: u8) -> Result<Edge, DecodeError> {
+    match tag {
+        0 => Ok(Edge::Start),
+        1 => Ok(Edge::End),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+pub(crate) fn period_tag(period: &Period) -> u8 {
+    match period {
+        Period::Week => 0,
+        Period::Month => 1,
+        Period::Year => 2,
+    }
+}
+
+pub(crate) fn period_from_tag(tag: u8) -> Result<Period, DecodeError> {
+    match tag {
+        0 => Ok(Period::Week),
+        1 => Ok(Period::Month),
+        2 => Ok(Period::Year),
+        tag => Err(DecodeError::InvalidTag(tag)),
+    }
+}
+
+fn write_weekday(bytes: &mut Vec<u8>, weekday: &Weekday) {
+    bytes.push(weekday_tag(weekday));
+}
+
+fn write_am_or_pm(bytes: &mut Vec<u8>, am_or_pm: &AMPM) {
+    bytes.push(am_or_pm_tag(am_or_pm));
+}
+
+fn write_quantifier(bytes: &mut Vec<u8>, quantifier: &Quantifier) {
+    bytes.push(quantifier_tag(quantifier));
+}
+
+fn write_modifier(bytes: &mut Vec<u8>, modifier: &Modifier) {
+    bytes.push(modifier_tag(modifier));
+}
+
+fn write_direction(bytes: &mut Vec<u8>, direction: &Direction) {
+    bytes.push(direction_tag(direction));
+}
+
+fn write_time_clue(bytes: &mut Vec<u8>, time_clue: &TimeClue) {
+    match time_clue {
+        TimeClue::Now => bytes.push(0),
+        TimeClue::Time(hms, am_or_pm) => {
+            bytes.push(1);
+            write_hms(bytes, hms);
+            write_option(bytes, am_or_pm, write_am_or_pm);
+        }
+        TimeClue::Midnight => bytes.push(2),
+        TimeClue::Relative(n, quantifier) => {
+            bytes.push(3);
+            write_usize(bytes, *n);
+            write_quantifier(bytes, quantifier);
+        }
+        TimeClue::RelativeDayAt(modifier, weekday, hms, am_or_pm) => {
+            bytes.push(4);
+            write_modifier(bytes, modifier);
+            write_weekday(bytes, weekday);
+            write_option(bytes, hms, write_hms);
+            write_option(bytes, am_or_pm, write_am_or_pm);
+        }
+        TimeClue::RelativeFuture(n, quantifier) => {
+            bytes.push(5);
+            write_usize(bytes, *n);
+            write_quantifier(bytes, quantifier);
+        }
+        TimeClue::SameWeekDayAt(weekday, hms, am_or_pm) => {
+            bytes.push(6);
+            write_weekday(bytes, weekday);
+            write_option(bytes, hms, write_hms);
+            write_option(bytes, am_or_pm, write_am_or_pm);
+        }
+        TimeClue::ShortcutDayAt(shortcut_day, hms, am_or_pm) => {
+            bytes.push(7);
+            bytes.push(shortcut_day_tag(shortcut_day));
+            write_option(bytes, hms, write_hms);
+            write_option(bytes, am_or_pm, write_am_or_pm);
+        }
+        TimeClue::ISO(ymd, hms) => {
+            bytes.push(8);
+            write_ymd(bytes, ymd);
+            write_hms(bytes, hms);
+        }
+        TimeClue::WeekdayISO(weekday, ymd, hms) => {
+            bytes.push(9);
+            write_weekday(bytes, weekday);
+            write_ymd(bytes, ymd);
+            write_hms(bytes, hms);
+        }
+        TimeClue::NthWeekday(n, weekday, direction) => {
+            bytes.push(10);
+            write_usize(bytes, *n);
+            write_weekday(bytes, weekday);
+            write_direction(bytes, direction);
+        }
+        TimeClue::WeekOfMonth(week, month) => {
+            bytes.push(11);
+            match week {
+                WeekOfMonth::Nth(n) => {
+                    bytes.push(0);
+                    write_u32(bytes, *n);
+                }
+                WeekOfMonth::Last => bytes.push(1),
+            }
+            match month {
+                MonthClue::Named(m) => {
+                    bytes.push(0);
+                    write_u32(bytes, *m);
+                }
+                MonthClue::This => bytes.push(1),
+                MonthClue::Relative(modifier) => {
+                    bytes.push(2);
+                    write_modifier(bytes, modifier);
+                }
+            }
+        }
+        TimeClue::PeriodEdge(edge, period) => {
+            bytes.push(12);
+            bytes.push(edge_tag(edge));
+            bytes.push(period_tag(period));
+        }
+        TimeClue::Offset(n, quantifier, direction, time_clue) => {
+            bytes.push(13);
+            write_usize(bytes, *n);
+            write_quantifier(bytes, quantifier);
+            write_direction(bytes, direction);
+            write_time_clue(bytes, time_clue);
+        }
+        TimeClue::PartialISO(year, month) => {
+            bytes.push(14);
+            write_i32(bytes, *year);
+            write_option(bytes, month, |bytes, m| write_u32(bytes, *m));
+        }
+        TimeClue::Ordinal(year, day) => {
+            bytes.push(15);
+            write_i32(bytes, *year);
+            write_u32(bytes, *day);
+        }
+        TimeClue::WithOffset(time_clue, offset) => {
+            bytes.push(16);
+            write_time_clue(bytes, time_clue);
+            write_i32(bytes, offset.local_minus_utc());
+        }
+        TimeClue::DayOfMonth(day, hms, am_or_pm) => {
+            bytes.push(17);
+            write_u32(bytes, *day);
+            write_option(bytes, hms, write_hms);
+            write_option(bytes, am_or_pm, write_am_or_pm);
+        }
+        TimeClue::Noon => bytes.push(18),
+        TimeClue::BareInt(n) => {
+            bytes.push(19);
+            write_u32(bytes, *n);
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        if self.bytes.len() < N {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        let mut array = [0; N];
+        array.copy_from_slice(taken);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, DecodeError> {
+        Ok(i32::from_le_bytes(self.take()?))
+    }
+
+    fn usize(&mut self) -> Result<usize, DecodeError> {
+        let n = u64::from_le_bytes(self.take()?);
+        usize::try_from(n).map_err(|_| DecodeError::InvalidValue)
+    }
+
+    fn hms(&mut self) -> Result<HMS, DecodeError> {
+        Ok((self.u32()?, self.u32()?, self.u32()?))
+    }
+
+    fn ymd(&mut self) -> Result<YMD, DecodeError> {
+        Ok((self.i32()?, self.u32()?, self.u32()?))
+    }
+
+    fn option<T>(
+        &mut self,
+        read: fn(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Option<T>, DecodeError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(read(self)?)),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+
+    fn weekday(&mut self) -> Result<Weekday, DecodeError> {
+        weekday_from_tag(self.u8()?)
+    }
+
+    fn am_or_pm(&mut self) -> Result<AMPM, DecodeError> {
+        am_or_pm_from_tag(self.u8()?)
+    }
+
+    fn quantifier(&mut self) -> Result<Quantifier, DecodeError> {
+        quantifier_from_tag(self.u8()?)
+    }
+
+    fn modifier(&mut self) -> Result<Modifier, DecodeError> {
+        modifier_from_tag(self.u8()?)
+    }
+
+    fn direction(&mut self) -> Result<Direction, DecodeError> {
+        direction_from_tag(self.u8()?)
+    }
+
+    fn nested_time_clue(&mut self) -> Result<TimeClue, DecodeError> {
+        if self.depth == MAX_DEPTH {
+            return Err(DecodeError::TooDeep);
+        }
+        self.depth += 1;
+        let time_clue = self.time_clue();
+        self.depth -= 1;
+        time_clue
+    }
+
+    fn time_clue(&mut self) -> Result<TimeClue, DecodeError> {
+        match self.u8()? {
+            0 => Ok(TimeClue::Now),
+            1 => Ok(TimeClue::Time(self.hms()?, self.option(Self::am_or_pm)?)),
+            2 => Ok(TimeClue::Midnight),
+            3 => Ok(TimeClue::Relative(self.usize()?, self.quantifier()?)),
+            4 => Ok(TimeClue::RelativeDayAt(
+                self.modifier()?,
+                self.weekday()?,
+                self.option(Self::hms)?,
+                self.option(Self::am_or_pm)?,
+            )),
+            5 => Ok(TimeClue::RelativeFuture(self.usize()?, self.quantifier()?)),
+            6 => Ok(TimeClue::SameWeekDayAt(
+                self.weekday()?,
+                self.option(Self::hms)?,
+                self.option(Self::am_or_pm)?,
+            )),
+            7 => Ok(TimeClue::ShortcutDayAt(
+                shortcut_day_from_tag(self.u8()?)?,
+                self.option(Self::hms)?,
+                self.option(Self::am_or_pm)?,
+            )),
+            8 => Ok(TimeClue::ISO(self.ymd()?, self.hms()?)),
+            9 => Ok(TimeClue::WeekdayISO(
+                self.weekday()?,
+                self.ymd()?,
+                self.hms()?,
+            )),
+            10 => Ok(TimeClue::NthWeekday(
+                self.usize()?,
+                self.weekday()?,
+                self.direction()?,
+            )),
+            11 => {
+                let week = match self.u8()? {
+                    0 => WeekOfMonth::Nth(self.u32()?),
+                    1 => WeekOfMonth::Last,
+                    tag => return Err(DecodeError::InvalidTag(tag)),
+                };
+                let month = match self.u8()? {
+                    0 => MonthClue::Named(self.u32()?),
+                    1 => MonthClue::This,
+                    2 => MonthClue::Relative(self.modifier()?),
+                    tag => return Err(DecodeError::InvalidTag(tag)),
+                };
+                Ok(TimeClue::WeekOfMonth(week, month))
+            }
+            12 => Ok(TimeClue::PeriodEdge(
+                edge_from_tag(self.u8()?)?,
+                period_from_tag(self.u8()?)?,
+            )),
+            13 => Ok(TimeClue::Offset(
+                self.usize()?,
+                self.quantifier()?,
+                self.direction()?,
+                Box::new(self.nested_time_clue()?),
+            )),
+            14 => Ok(TimeClue::PartialISO(self.i32()?, self.option(Self::u32)?)),
+            15 => Ok(TimeClue::Ordinal(self.i32()?, self.u32()?)),
+            16 => {
+                let time_clue = self.nested_time_clue()?;
+                let offset = FixedOffset::east_opt(self.i32()?).ok_or(DecodeError::InvalidValue)?;
+                Ok(TimeClue::WithOffset(Box::new(time_clue), offset))
+            }
+            17 => Ok(TimeClue::DayOfMonth(
+                self.u32()?,
+                self.option(Self::hms)?,
+                self.option(Self::am_or_pm)?,
+            )),
+            18 => Ok(TimeClue::Noon),
+            19 => Ok(TimeClue::BareInt(self.u32()?)),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::binary::{DecodeError, VERSION};
+    use crate::parser::{parse_time_clue_from_str, TimeClue};
+
+    #[test]
+    fn test_round_trip() {
+        for s in [
+            "now",
+            "7:30pm",
+            "midnight",
+            "noon",
+            "15",
+            "4 min ago",
+            "last friday at 19:43",
+            "in 2 weeks",
+            "2 decades ago",
+            "a century ago",
+            "monday at 6 am",
+            "tomorrow at 9",
+            "2020-12-25T19:43:00",
+            "friday 2020-12-25",
+            "3 mondays from now",
+            "second week of next month",
+            "last week of december",
+            "3 days before end of month",
+            "2020-12",
+            "2020",
+            "2020-360",
+            "tomorrow at 9 -0800",
+            "the 22nd at 5pm",
+        ]
+        .iter()
+        {
+            let time_clue = parse_time_clue_from_str(s).unwrap();
+            let bytes = time_clue.to_bytes();
+            assert_eq!(bytes[0], VERSION);
+            assert_eq!(TimeClue::from_bytes(&bytes).unwrap(), time_clue, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_from_bytes_err() {
+        let bytes = parse_time_clue_from_str("in 2 weeks").unwrap().to_bytes();
+        assert_eq!(TimeClue::from_bytes(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            TimeClue::from_bytes(&[VERSION + 1, 0]),
+            Err(DecodeError::UnsupportedVersion(VERSION + 1))
+        );
+        assert_eq!(
+            TimeClue::from_bytes(&[VERSION, 255]),
+            Err(DecodeError::InvalidTag(255))
+        );
+        assert_eq!(
+            TimeClue::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            TimeClue::from_bytes(&trailing),
+            Err(DecodeError::TrailingBytes(1))
+        );
+        let mut deep = vec![VERSION];
+        deep.resize(1_000_001, 16);
+        assert_eq!(TimeClue::from_bytes(&deep), Err(DecodeError::TooDeep));
+    }
+}
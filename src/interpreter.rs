@@ -1,5 +1,12 @@
-use crate::parser::{Modifier, Quantifier, ShortcutDay, TimeClue, AMPM, HMS};
-use chrono::{DateTime, Datelike, Duration, LocalResult, TimeZone, Utc};
+use crate::parser::{
+    Direction, Edge, Modifier, MonthClue, Period, Quantifier, RangeClue, Recurrence,
+    RecurrenceUnit, ShortcutDay, TimeClue, WeekOfMonth, AMPM, HMS,
+};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
+use std::convert::TryFrom;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -22,33 +29,368 @@ pub enum EvaluationError {
         minute: u32,
         second: u32,
     },
+    #[error("nonexistent local time: {0}")]
+    NonExistentLocalTime(NaiveDateTime),
+    #[error("out of range")]
+    OutOfRange,
+    #[error("not a date: the time clue has a time component")]
+    NotADate,
+    #[error("invalid date: {year}-{month:02} has no day {day}")]
+    DayOutOfRangeForMonth { year: i32, month: u32, day: u32 },
+    #[error("invalid ordinal date: {year}-{day:03}")]
+    InvalidOrdinalDate { year: i32, day: u32 },
+    #[error("{date} is not a {weekday}")]
+    WeekdayDateMismatch { weekday: Weekday, date: NaiveDate },
+    #[error("strict: zero {0:?}")]
+    ZeroMagnitude(Quantifier),
+    #[error("strict: {n} {quantifier:?} exceeds one year")]
+    ExtremeMagnitude { n: usize, quantifier: Quantifier },
+}
+
+/// How out of range time components are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ClampTime {
+    /// Out of range time components are rejected e.g. "19:60" is an invalid time.
+    #[default]
+    Off,
+    /// Out of range minutes and seconds carry over into the next unit
+    /// e.g. "19:60" is 20:00:00, "19:43:60" is 19:44:00.
+    /// Resulting hours beyond 23 are rejected.
+    MinutesSeconds,
+    /// Same as `MinutesSeconds` but hours beyond 23 also carry over into the following day
+    /// e.g. "23:60" is 00:00:00 of the following day.
+    Hours,
+}
+
+/// How a bare integer such as "15" is interpreted.
+///
+/// Only bare integers (`TimeClue::BareInt`) are affected, "15:00", "3pm" or "noon" are always times.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum BareIntMeaning {
+    /// "15" is 15:00 of the current day.
+    #[default]
+    Hour,
+    /// "15" is the 15th of the current month at 00:00, when `1 <= n <= 31`.
+    /// Days not in the current month e.g. "31" in june are rejected.
+    DayOfMonth,
+}
+
+/// How bare hours without am/pm such as "7" are interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ClockHint {
+    /// 24-hour clock: "7" is 07:00.
+    #[default]
+    None,
+    /// Hours in `1..=12` resolve to whichever of am or pm is closest to `now`
+    /// e.g. "7" is 19:00 at 18:00. Ties resolve to am.
+    /// Explicit am/pm e.g. "7am" and "noon" are never changed.
+    NearestToNow,
+}
+
+/// Options controlling how time clues are evaluated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    /// * if true: times without a day will be interpreted as times during the following the day.
+    ///   e.g. 19:43 will be interpreted as tomorrow at 19:43 if current time is > 19:43.
+    ///   am/pm times are compared once converted e.g. "7pm" at 20:00 is tomorrow at 19:00.
+    /// * if false: times without a day will be interpreted as times during current day.
+    pub assume_next_day: bool,
+    /// * if true: `midnight` will be interpreted as 00:00 of the current day.
+    /// * if false: `midnight` will be interpreted as 00:00 of the following day,
+    ///   i.e. the upcoming midnight.
+    pub strict_midnight: bool,
+    /// * if true: `24:00:00` is accepted and interpreted as 00:00:00 of the following day.
+    /// * if false: `24:00:00` is rejected as an invalid time.
+    pub allow_hour_24: bool,
+    /// How out of range time components are handled, see `ClampTime`.
+    pub clamp_time: ClampTime,
+    /// * if true: weeks are shifted using calendar arithmetic, i.e. same weekday and wall-clock time
+    ///   e.g. "in 1 week" keeps the same local time across a DST change.
+    /// * if false: weeks are fixed durations of 7 * 24 hours.
+    pub calendar_weeks: bool,
+    /// How bare integers are interpreted, see `BareIntMeaning`.
+    pub bare_int: BareIntMeaning,
+    /// How bare hours without am/pm are interpreted, see `ClockHint`.
+    pub clock_hint: ClockHint,
+    /// * if true: grammatically valid but semantically odd time clues are rejected:
+    ///   * zero magnitudes e.g. "0 days ago", "in 0 min", "0 mondays from now":
+    ///     `EvaluationError::ZeroMagnitude`
+    ///   * magnitudes exceeding one year e.g. "30 months ago", "in 400 days":
+    ///     `EvaluationError::ExtremeMagnitude`
+    /// * if false: such time clues are evaluated.
+    ///
+    /// Contradictory modifiers e.g. "next yesterday", "in 2 days ago" are always rejected when parsing.
+    pub strict: bool,
+    /// * if true: weekdays and times without a day resolve to their latest past occurrence
+    ///   when they would otherwise be in the future.
+    ///   e.g. "friday" on a wednesday is last week's friday, "19:43" at 12:00 is yesterday at 19:43.
+    ///   Takes precedence over `assume_next_day`.
+    /// * if false: weekdays resolve to the current week and times to the current day.
+    pub prefer_past: bool,
+}
+
+/// 24-hour clock hour from `h` and `am_or_pm_maybe`.
+///
+/// When `am_or_pm_maybe` is set, `h` must be a 12-hour clock hour in `1..=12`:
+/// 12am is 0, 12pm is 12, 0am and 13pm are invalid.
+fn hour_24(h: u32, am_or_pm_maybe: &Option<AMPM>) -> Option<u32> {
+    match am_or_pm_maybe {
+        None => Some(h),
+        Some(_) if h == 0 || h > 12 => None,
+        Some(AMPM::AM) => Some(h % 12),
+        Some(AMPM::PM) => Some(h % 12 + 12),
+    }
+}
+
+fn invalid_time(hms: HMS, am_or_pm_maybe: Option<AMPM>) -> EvaluationError {
+    let (h, m, s) = hms;
+    match am_or_pm_maybe {
+        Some(am_or_pm) => EvaluationError::InvalidTimeAMPM {
+            hour: h,
+            minute: m,
+            second: s,
+            am_or_pm,
+        },
+        None => EvaluationError::InvalidTime {
+            hour: h,
+            minute: m,
+            second: s,
+        },
+    }
 }
 
 fn check_hms(hms: HMS, am_or_pm_maybe: Option<AMPM>) -> Result<HMS, EvaluationError> {
     let (h, m, s) = hms;
-    let h_am_pm = match am_or_pm_maybe {
-        None | Some(AMPM::AM) => h,
-        Some(AMPM::PM) => h + 12,
+    match hour_24(h, &am_or_pm_maybe) {
+        Some(h_am_pm) if h_am_pm < 24 && m < 60 && s < 60 => Ok((h_am_pm, m, s)),
+        _ => Err(invalid_time(hms, am_or_pm_maybe)),
+    }
+}
+
+/// Same as `check_hms(hms, am_or_pm_maybe)` but honors `options`.
+///
+/// Returns the checked time along with the number of days it carries over
+/// e.g. `24:00:00` is `00:00:00` of the following day when `options.allow_hour_24` is set.
+fn check_time(
+    hms: HMS,
+    am_or_pm_maybe: Option<AMPM>,
+    options: &ParseOptions,
+) -> Result<(HMS, Duration), EvaluationError> {
+    match (hms, &am_or_pm_maybe) {
+        ((24, 0, 0), None) if options.allow_hour_24 => Ok(((0, 0, 0), Duration::days(1))),
+        ((h, m, s), _) => {
+            if options.clamp_time == ClampTime::Off {
+                return Ok((check_hms(hms, am_or_pm_maybe)?, Duration::zero()));
+            }
+            let h = match hour_24(h, &am_or_pm_maybe) {
+                Some(h) => h,
+                None => return Err(invalid_time(hms, am_or_pm_maybe)),
+            };
+            let (m, s) = (m + s / 60, s % 60);
+            let (h, m) = (h + m / 60, m % 60);
+            match options.clamp_time {
+                ClampTime::Hours => Ok(((h % 24, m, s), Duration::days((h / 24) as i64))),
+                _ if h < 24 => Ok(((h, m, s), Duration::zero())),
+                _ => Err(invalid_time(hms, am_or_pm_maybe)),
+            }
+        }
+    }
+}
+
+/// Local time `hms` on `date` in the timezone of `now`.
+///
+/// `hms` must have been checked using `check_hms`.
+/// Ambiguous local times (e.g. DST fall back) resolve to the earliest one.
+fn at_hms<Tz: chrono::TimeZone>(
+    now: &DateTime<Tz>,
+    date: NaiveDate,
+    hms: HMS,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    let (h, m, s) = hms;
+    let naive = date
+        .and_hms_opt(h, m, s)
+        .ok_or(EvaluationError::InvalidTime {
+            hour: h,
+            minute: m,
+            second: s,
+        })?;
+    at_local(now, naive)
+}
+
+/// Fails with `EvaluationError::OutOfRange` if `year` is not supported by chrono.
+fn check_year(year: i32) -> Result<(), EvaluationError> {
+    if (NaiveDate::MIN.year()..=NaiveDate::MAX.year()).contains(&year) {
+        Ok(())
+    } else {
+        Err(EvaluationError::OutOfRange)
+    }
+}
+
+/// Fails with `EvaluationError::DayOutOfRangeForMonth` if `month` of `year` has no `day` e.g. 2021-02-29.
+fn check_day(year: i32, month: u32, day: u32) -> Result<(), EvaluationError> {
+    if (1..=12).contains(&month) && day >= 1 && NaiveDate::from_ymd_opt(year, month, day).is_none()
+    {
+        Err(EvaluationError::DayOutOfRangeForMonth { year, month, day })
+    } else {
+        Ok(())
+    }
+}
+
+/// Local datetime `naive` in the timezone of `now`.
+///
+/// Ambiguous local times (e.g. DST fall back) resolve to the earliest one.
+fn at_local<Tz: chrono::TimeZone>(
+    now: &DateTime<Tz>,
+    naive: NaiveDateTime,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    match now.timezone().from_local_datetime(&naive) {
+        LocalResult::Single(datetime) => Ok(datetime),
+        LocalResult::Ambiguous(earliest, _) => Ok(earliest),
+        LocalResult::None => Err(EvaluationError::NonExistentLocalTime(naive)),
+    }
+}
+
+/// Duration of `n` times `quantifier`, `None` if out of range.
+pub(crate) fn relative_duration(n: usize, quantifier: &Quantifier) -> Option<Duration> {
+    let n = i64::try_from(n).ok()?;
+    match quantifier {
+        Quantifier::Min => Duration::try_minutes(n),
+        Quantifier::Hours => Duration::try_hours(n),
+        Quantifier::Days => Duration::try_days(n),
+        Quantifier::Weeks => Duration::try_weeks(n),
+        Quantifier::Months => Duration::try_days(n.checked_mul(30)?), // assume 1 month = 30 days
+        Quantifier::Decades => Duration::try_days(n.checked_mul(10 * 365)?), // assume 1 year = 365 days
+        Quantifier::Centuries => Duration::try_days(n.checked_mul(100 * 365)?),
+    }
+}
+
+/// Number of calendar months in one `quantifier`, `None` if `quantifier` is shorter than a month.
+fn months_per(quantifier: &Quantifier) -> Option<usize> {
+    match quantifier {
+        Quantifier::Months => Some(1),
+        Quantifier::Decades => Some(10 * 12),
+        Quantifier::Centuries => Some(100 * 12),
+        _ => None,
+    }
+}
+
+/// `now` shifted by `n` times `quantifier`, towards the future if `future` else towards the past.
+///
+/// Unlike `relative_duration`, months, decades and centuries are shifted using calendar arithmetic
+/// e.g. 1 month before 2020-03-31 is 2020-02-29.
+fn calendar_shift<Tz: chrono::TimeZone>(
+    now: DateTime<Tz>,
+    n: usize,
+    quantifier: &Quantifier,
+    future: bool,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    let shifted = match months_per(quantifier) {
+        Some(months_per_unit) => {
+            let months = n
+                .checked_mul(months_per_unit)
+                .and_then(|months| u32::try_from(months).ok())
+                .ok_or(EvaluationError::OutOfRange)?;
+            let months = Months::new(months);
+            if future {
+                now.checked_add_months(months)
+            } else {
+                now.checked_sub_months(months)
+            }
+        }
+        None => {
+            let duration = relative_duration(n, quantifier).ok_or(EvaluationError::OutOfRange)?;
+            if future {
+                now.checked_add_signed(duration)
+            } else {
+                now.checked_sub_signed(duration)
+            }
+        }
+    };
+    shifted.ok_or(EvaluationError::OutOfRange)
+}
+
+/// Check `time_clue` against the rules of `ParseOptions::strict`.
+fn check_strict(time_clue: &TimeClue) -> Result<(), EvaluationError> {
+    let (n, quantifier) = match time_clue {
+        TimeClue::Relative(n, quantifier)
+        | TimeClue::RelativeFuture(n, quantifier)
+        | TimeClue::Offset(n, quantifier, _, _) => (*n, quantifier.clone()),
+        TimeClue::NthWeekday(n, _, _) => (*n, Quantifier::Weeks),
+        _ => return Ok(()),
+    };
+    let one_year = match quantifier {
+        Quantifier::Min => 365 * 24 * 60,
+        Quantifier::Hours => 365 * 24,
+        Quantifier::Days => 365,
+        Quantifier::Weeks => 52,
+        Quantifier::Months => 12,
+        Quantifier::Decades | Quantifier::Centuries => 0, // always more than one year
     };
-    if h_am_pm < 24 && m < 60 && s < 60 {
-        Ok((h_am_pm, m, s))
+    if n == 0 {
+        Err(EvaluationError::ZeroMagnitude(quantifier))
+    } else if n > one_year {
+        Err(EvaluationError::ExtremeMagnitude { n, quantifier })
     } else {
-        match am_or_pm_maybe {
-            Some(am_or_pm) => Err(EvaluationError::InvalidTimeAMPM {
-                hour: h,
-                minute: m,
-                second: s,
-                am_or_pm,
-            }),
-            None => Err(EvaluationError::InvalidTime {
-                hour: h,
-                minute: m,
-                second: s,
-            }),
+        Ok(())
+    }
+}
+
+/// `now` shifted by `n` times `quantifier` for relative time clues, see `ParseOptions::calendar_weeks`.
+fn relative_shift<Tz: chrono::TimeZone>(
+    now: DateTime<Tz>,
+    n: usize,
+    quantifier: &Quantifier,
+    future: bool,
+    options: &ParseOptions,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    match quantifier {
+        Quantifier::Weeks if options.calendar_weeks => {
+            let weeks = relative_duration(n, quantifier).ok_or(EvaluationError::OutOfRange)?;
+            let naive = if future {
+                now.naive_local().checked_add_signed(weeks)
+            } else {
+                now.naive_local().checked_sub_signed(weeks)
+            };
+            at_local(&now, naive.ok_or(EvaluationError::OutOfRange)?)
+        }
+        Quantifier::Decades | Quantifier::Centuries => calendar_shift(now, n, quantifier, future),
+        _ => {
+            let duration = relative_duration(n, quantifier).ok_or(EvaluationError::OutOfRange)?;
+            let shifted = if future {
+                now.checked_add_signed(duration)
+            } else {
+                now.checked_sub_signed(duration)
+            };
+            shifted.ok_or(EvaluationError::OutOfRange)
         }
     }
 }
 
+/// Start of the week containing `date`, i.e. the latest `week_start` on or before `date`.
+fn current_week_anchor(date: NaiveDate, week_start: Weekday) -> NaiveDate {
+    let days_since_week_start =
+        (7 + date.weekday().num_days_from_monday() - week_start.num_days_from_monday()) % 7;
+    date - Duration::days(days_since_week_start as i64)
+}
+
+/// Start of the week containing `now`, i.e. the latest `week_start` at 00:00 on or before `now`.
+///
+/// e.g. with `week_start` = `Weekday::Mon`, the anchor of sunday 2020-07-12 is monday 2020-07-06 at 00:00.
+pub fn week_anchor<Tz: chrono::TimeZone>(
+    now: DateTime<Tz>,
+    week_start: Weekday,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    let anchor = current_week_anchor(now.date_naive(), week_start);
+    at_hms(&now, anchor, (0, 0, 0))
+}
+
+/// Same as `week_anchor(now, Weekday::Mon)`: monday at 00:00 of the week containing `now`.
+pub fn week_start_monday<Tz: chrono::TimeZone>(
+    now: DateTime<Tz>,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    week_anchor(now, Weekday::Mon)
+}
+
 /// Same as `evaluate(time_clue, now)`
 pub fn evaluate<Tz: chrono::TimeZone>(
     time_clue: TimeClue,
@@ -61,82 +403,148 @@ pub fn evaluate<Tz: chrono::TimeZone>(
 ///
 /// `assume_next_day`:
 /// * if true: times without a day will be interpreted as times during the following the day.
-/// e.g. 19:43 will be interpreted as tomorrow at 19:43 if current time is > 19:43.
+///   e.g. 19:43 will be interpreted as tomorrow at 19:43 if current time is > 19:43.
 /// * if false: times without a day will be interpreted as times during current day.
 pub fn evaluate_time_clue<Tz: chrono::TimeZone>(
     time_clue: TimeClue,
     now: DateTime<Tz>,
     assume_next_day: bool, // assume next day if only time is supplied and time < now
 ) -> Result<DateTime<Tz>, EvaluationError> {
+    let options = ParseOptions {
+        assume_next_day,
+        ..Default::default()
+    };
+    evaluate_with_options(time_clue, now, &options)
+}
+
+/// Evaluate `time_clue` given reference time `now` and evaluation `options`.
+pub fn evaluate_with_options<Tz: chrono::TimeZone>(
+    time_clue: TimeClue,
+    now: DateTime<Tz>,
+    options: &ParseOptions,
+) -> Result<DateTime<Tz>, EvaluationError> {
+    if options.strict {
+        check_strict(&time_clue)?;
+    }
+    let today = now.date_naive();
     match time_clue {
         TimeClue::Now => Ok(now),
+        TimeClue::BareInt(day)
+            if options.bare_int == BareIntMeaning::DayOfMonth && (1..=31).contains(&day) =>
+        {
+            evaluate_with_options(TimeClue::DayOfMonth(day, None, None), now, options)
+        }
+        TimeClue::BareInt(h) => {
+            evaluate_with_options(TimeClue::Time((h, 0, 0), None), now, options)
+        }
+        TimeClue::Time((h, m, s), None)
+            if options.clock_hint == ClockHint::NearestToNow && (1..=12).contains(&h) =>
+        {
+            let options = ParseOptions {
+                clock_hint: ClockHint::None,
+                ..options.clone()
+            };
+            let am = evaluate_with_options(
+                TimeClue::Time((h, m, s), Some(AMPM::AM)),
+                now.clone(),
+                &options,
+            )?;
+            let pm = evaluate_with_options(
+                TimeClue::Time((h, m, s), Some(AMPM::PM)),
+                now.clone(),
+                &options,
+            )?;
+            let distance = |d: &DateTime<Tz>| d.clone().signed_duration_since(now.clone()).abs();
+            if distance(&pm) < distance(&am) {
+                Ok(pm)
+            } else {
+                Ok(am)
+            }
+        }
         TimeClue::Time((h, m, s), am_or_pm_maybe) => {
-            let (h, m, s) = check_hms((h, m, s), am_or_pm_maybe)?;
-            let d = now.date().and_hms(h, m, s);
-            if assume_next_day && d < now {
-                Ok(d + Duration::days(1))
+            let (hms, carry) = check_time((h, m, s), am_or_pm_maybe, options)?;
+            let d = at_hms(&now, today + carry, hms)?;
+            if options.prefer_past && d > now {
+                at_hms(&now, today + carry - Duration::days(1), hms)
+            } else if options.assume_next_day && d < now {
+                at_hms(&now, today + carry + Duration::days(1), hms)
             } else {
                 Ok(d)
             }
         }
-        TimeClue::Relative(n, quantifier) => match quantifier {
-            Quantifier::Min => Ok(now - Duration::minutes(n as i64)),
-            Quantifier::Hours => Ok(now - Duration::hours(n as i64)),
-            Quantifier::Days => Ok(now - Duration::days(n as i64)),
-            Quantifier::Weeks => Ok(now - Duration::weeks(n as i64)),
-            Quantifier::Months => Ok(now - Duration::days(30 * n as i64)), // assume 1 month = 30 days
-        },
-        TimeClue::RelativeFuture(n, quantifier) => match quantifier {
-            Quantifier::Min => Ok(now + Duration::minutes(n as i64)),
-            Quantifier::Hours => Ok(now + Duration::hours(n as i64)),
-            Quantifier::Days => Ok(now + Duration::days(n as i64)),
-            Quantifier::Weeks => Ok(now + Duration::weeks(n as i64)),
-            Quantifier::Months => Ok(now + Duration::days(30 * n as i64)), // assume 1 month = 30 days
-        },
+        // noon is never ambiguous, see `ClockHint::NearestToNow`
+        TimeClue::Noon => {
+            evaluate_with_options(TimeClue::Time((12, 0, 0), Some(AMPM::PM)), now, options)
+        }
+        TimeClue::Midnight => {
+            if options.strict_midnight {
+                at_hms(&now, today, (0, 0, 0))
+            } else {
+                at_hms(&now, today + Duration::days(1), (0, 0, 0))
+            }
+        }
+        TimeClue::Relative(n, quantifier) => relative_shift(now, n, &quantifier, false, options),
+        TimeClue::RelativeFuture(n, quantifier) => {
+            relative_shift(now, n, &quantifier, true, options)
+        }
         TimeClue::RelativeDayAt(modifier, weekday, hms_maybe, am_or_pm_maybe) => {
             let (h, m, s) = hms_maybe.unwrap_or((0, 0, 0));
-            let (h, m, s) = check_hms((h, m, s), am_or_pm_maybe)?;
-            let monday = now.date() - Duration::days(now.weekday().num_days_from_monday() as i64);
+            let (hms, carry) = check_time((h, m, s), am_or_pm_maybe, options)?;
+            let monday = current_week_anchor(today, Weekday::Mon);
+            let same_week_day =
+                monday + (Duration::days(weekday.num_days_from_monday() as i64)) + carry;
+            // next/last never resolve to today: "next friday" on a friday is in 7 days
             match modifier {
                 Modifier::Last => {
-                    let same_week_day =
-                        monday + (Duration::days(weekday.num_days_from_monday() as i64));
                     if weekday.num_days_from_monday() < now.weekday().num_days_from_monday() {
-                        Ok(same_week_day.and_hms(h, m, s)) // same week
+                        at_hms(&now, same_week_day, hms) // same week
                     } else {
-                        Ok(same_week_day.and_hms(h, m, s) - Duration::days(7)) // last week
+                        at_hms(&now, same_week_day - Duration::days(7), hms) // last week
                     }
                 }
                 Modifier::Next => {
-                    let same_week_day =
-                        monday + (Duration::days(weekday.num_days_from_monday() as i64));
                     if weekday.num_days_from_monday() > now.weekday().num_days_from_monday() {
-                        Ok(same_week_day.and_hms(h, m, s)) // same week
+                        at_hms(&now, same_week_day, hms) // same week
                     } else {
-                        Ok(same_week_day.and_hms(h, m, s) + Duration::days(7)) // next week
+                        at_hms(&now, same_week_day + Duration::days(7), hms) // next week
                     }
                 }
             }
         }
         TimeClue::SameWeekDayAt(weekday, hms_maybe, am_or_pm_maybe) => {
             let (h, m, s) = hms_maybe.unwrap_or((0, 0, 0));
-            let (h, m, s) = check_hms((h, m, s), am_or_pm_maybe)?;
-            let monday = now.date() - Duration::days(now.weekday().num_days_from_monday() as i64);
-            Ok((monday + Duration::days(weekday.num_days_from_monday() as i64)).and_hms(h, m, s))
+            let (hms, carry) = check_time((h, m, s), am_or_pm_maybe, options)?;
+            let monday = current_week_anchor(today, Weekday::Mon);
+            let same_week_day =
+                monday + Duration::days(weekday.num_days_from_monday() as i64) + carry;
+            let d = at_hms(&now, same_week_day, hms)?;
+            if options.prefer_past && d > now {
+                at_hms(&now, same_week_day - Duration::days(7), hms)
+            } else {
+                Ok(d)
+            }
         }
         TimeClue::ShortcutDayAt(rday, hms_maybe, am_or_pm_maybe) => {
             let (h, m, s) = hms_maybe.unwrap_or((0, 0, 0));
-            let (h, m, s) = check_hms((h, m, s), am_or_pm_maybe)?;
+            let (hms, carry) = check_time((h, m, s), am_or_pm_maybe, options)?;
+            // shift the calendar date rather than 24 hours so DST changes keep the requested time
+            let today = today + carry;
             match rday {
-                ShortcutDay::Today => Ok(now.date().and_hms(h, m, s)),
-                ShortcutDay::Yesterday => Ok((now.date() - Duration::days(1)).and_hms(h, m, s)),
-                ShortcutDay::Tomorrow => Ok((now.date() + Duration::days(1)).and_hms(h, m, s)),
+                ShortcutDay::Today => at_hms(&now, today, hms),
+                ShortcutDay::Yesterday => at_hms(&now, today - Duration::days(1), hms),
+                ShortcutDay::Tomorrow => at_hms(&now, today + Duration::days(1), hms),
             }
         }
         TimeClue::ISO((year, month, day), (h, m, s)) => {
-            let utc = Utc.ymd_opt(year, month, day).and_hms_opt(h, m, s);
+            check_year(year)?;
+            check_day(year, month, day)?;
+            let (hms, carry) = match (h, m, s) {
+                (24, 0, 0) if options.allow_hour_24 => ((0, 0, 0), Duration::days(1)),
+                _ => ((h, m, s), Duration::zero()),
+            };
+            let utc = Utc.with_ymd_and_hms(year, month, day, hms.0, hms.1, hms.2);
             match utc {
-                LocalResult::Single(utc) => Ok(utc.with_timezone(&now.timezone())),
+                LocalResult::Single(utc) => Ok((utc + carry).with_timezone(&now.timezone())),
                 _ => Err(EvaluationError::ChronoISOError {
                     year,
                     month,
@@ -147,22 +555,395 @@ pub fn evaluate_time_clue<Tz: chrono::TimeZone>(
                 }),
             }
         }
+        TimeClue::WeekdayISO(weekday, (year, month, day), hms) => {
+            if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                if date.weekday() != weekday {
+                    return Err(EvaluationError::WeekdayDateMismatch { weekday, date });
+                }
+            }
+            evaluate_with_options(TimeClue::ISO((year, month, day), hms), now, options)
+        }
+        TimeClue::WeekOfMonth(week, month) => {
+            let this_month = today.with_day(1).ok_or(EvaluationError::OutOfRange)?;
+            let first = match month {
+                MonthClue::Named(m) => this_month.with_month(m),
+                MonthClue::This => Some(this_month),
+                MonthClue::Relative(Modifier::Next) => {
+                    this_month.checked_add_months(Months::new(1))
+                }
+                MonthClue::Relative(Modifier::Last) => {
+                    this_month.checked_sub_months(Months::new(1))
+                }
+            }
+            .ok_or(EvaluationError::OutOfRange)?;
+            let last = first
+                .checked_add_months(Months::new(1))
+                .and_then(|next_month| next_month.pred_opt())
+                .ok_or(EvaluationError::OutOfRange)?;
+            let week_start = match week {
+                WeekOfMonth::Nth(n) => {
+                    current_week_anchor(first, Weekday::Mon)
+                        + Duration::weeks(n.saturating_sub(1) as i64)
+                }
+                WeekOfMonth::Last => current_week_anchor(last, Weekday::Mon),
+            };
+            if week_start > last {
+                return Err(EvaluationError::OutOfRange); // e.g. fifth week of a 4 weeks month
+            }
+            at_hms(&now, week_start, (0, 0, 0))
+        }
+        TimeClue::NthWeekday(n, weekday, direction) => {
+            let weeks = n
+                .checked_sub(1)
+                .and_then(|weeks| i64::try_from(weeks).ok())
+                .and_then(Duration::try_weeks)
+                .ok_or(EvaluationError::OutOfRange)?;
+            let modifier = match direction {
+                Direction::After => Modifier::Next,
+                Direction::Before => Modifier::Last,
+            };
+            // first occurrence, then the remaining weeks
+            let first = evaluate_with_options(
+                TimeClue::RelativeDayAt(modifier, weekday, None, None),
+                now.clone(),
+                options,
+            )?
+            .date_naive();
+            let nth = match direction {
+                Direction::After => first.checked_add_signed(weeks),
+                Direction::Before => first.checked_sub_signed(weeks),
+            };
+            at_hms(&now, nth.ok_or(EvaluationError::OutOfRange)?, (0, 0, 0))
+        }
+        TimeClue::DayOfMonth(day, hms_maybe, am_or_pm_maybe) => {
+            let (hms, carry) = check_time(hms_maybe.unwrap_or((0, 0, 0)), am_or_pm_maybe, options)?;
+            match today.with_day(day) {
+                Some(date) => at_hms(&now, date + carry, hms),
+                None => Err(EvaluationError::DayOutOfRangeForMonth {
+                    year: today.year(),
+                    month: today.month(),
+                    day,
+                }),
+            }
+        }
+        TimeClue::PeriodEdge(edge, period) => {
+            let (start, end) = match period {
+                Period::Week => {
+                    let monday = current_week_anchor(today, Weekday::Mon);
+                    (Some(monday), Some(monday + Duration::days(6)))
+                }
+                Period::Month => {
+                    let first = today.with_day(1);
+                    let last = first
+                        .and_then(|first| first.checked_add_months(Months::new(1)))
+                        .and_then(|next_month| next_month.pred_opt());
+                    (first, last)
+                }
+                Period::Year => (
+                    today.with_ordinal(1),
+                    today.with_month(12).and_then(|d| d.with_day(31)),
+                ),
+            };
+            let date = match edge {
+                Edge::Start => start,
+                Edge::End => end,
+            };
+            at_hms(&now, date.ok_or(EvaluationError::OutOfRange)?, (0, 0, 0))
+        }
+        TimeClue::Offset(n, quantifier, direction, time_clue) => {
+            let anchor = evaluate_with_options(*time_clue, now, options)?;
+            calendar_shift(anchor, n, &quantifier, direction == Direction::After)
+        }
+        TimeClue::PartialISO(year, month) => {
+            let (start, _) = partial_iso_range(year, month)?;
+            Ok(start
+                .and_time(NaiveTime::MIN)
+                .and_utc()
+                .with_timezone(&now.timezone()))
+        }
+        TimeClue::Ordinal(year, day) => {
+            check_year(year)?;
+            match NaiveDate::from_yo_opt(year, day) {
+                Some(date) => Ok(date
+                    .and_time(NaiveTime::MIN)
+                    .and_utc()
+                    .with_timezone(&now.timezone())),
+                None => Err(EvaluationError::InvalidOrdinalDate { year, day }),
+            }
+        }
+        TimeClue::WithOffset(time_clue, offset) => {
+            let datetime = evaluate_with_options(*time_clue, now.with_timezone(&offset), options)?;
+            Ok(datetime.with_timezone(&now.timezone()))
+        }
+    }
+}
+
+/// Evaluate date only `time_clue` given reference time `now`, see `TimeClue::is_date_only`.
+///
+/// Fails with `EvaluationError::NotADate` if `time_clue` has a time component e.g. "9am".
+pub fn evaluate_date<Tz: chrono::TimeZone>(
+    time_clue: TimeClue,
+    now: DateTime<Tz>,
+    options: &ParseOptions,
+) -> Result<NaiveDate, EvaluationError> {
+    if !time_clue.is_date_only() {
+        return Err(EvaluationError::NotADate);
+    }
+    // evaluate on the wall clock of `now`: dates do not depend on DST transitions
+    let wall_clock = now.naive_local().and_utc();
+    let datetime = evaluate_with_options(time_clue, wall_clock, options)?;
+    Ok(datetime.date_naive())
+}
+
+/// Same as `evaluate_with_options(time_clue, now, options)` but also returns
+/// the duration `now` was shifted by for relative time clues.
+///
+/// The duration is:
+/// * `Some(duration)` for `Relative` and `RelativeFuture` time clues,
+///   `duration` being the magnitude of the shift e.g. 4 minutes for "4 min ago",
+///   measured between `now` and the result since decades, centuries and
+///   `ParseOptions::calendar_weeks` weeks are calendar shifts.
+/// * `None` for other time clues.
+pub fn evaluate_with_delta<Tz: chrono::TimeZone>(
+    time_clue: TimeClue,
+    now: DateTime<Tz>,
+    options: &ParseOptions,
+) -> Result<(DateTime<Tz>, Option<Duration>), EvaluationError> {
+    let relative = matches!(
+        time_clue,
+        TimeClue::Relative(_, _) | TimeClue::RelativeFuture(_, _)
+    );
+    let datetime = evaluate_with_options(time_clue, now.clone(), options)?;
+    let delta = if relative {
+        Some(datetime.clone().signed_duration_since(now).abs())
+    } else {
+        None
+    };
+    Ok((datetime, delta))
+}
+
+/// Start and end (exclusive) in UTC of ISO `year` or `month` of `year`.
+fn partial_iso_range(
+    year: i32,
+    month: Option<u32>,
+) -> Result<(NaiveDate, NaiveDate), EvaluationError> {
+    check_year(year)?;
+    let start = NaiveDate::from_ymd_opt(year, month.unwrap_or(1), 1).ok_or(
+        EvaluationError::ChronoISOError {
+            year,
+            month: month.unwrap_or(1),
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        },
+    )?;
+    let end = match month {
+        Some(_) => start.checked_add_months(Months::new(1)),
+        None => start.checked_add_months(Months::new(12)),
+    };
+    Ok((start, end.ok_or(EvaluationError::OutOfRange)?))
+}
+
+/// Evaluate `range_clue` given reference time `now`.
+///
+/// Returns the `(start, end)` of the range.
+/// Months are shifted using calendar arithmetic e.g. "last month" on 2020-03-31 starts on 2020-02-29.
+/// ISO years and months span their whole period in UTC, the end being exclusive
+/// e.g. "2020-12" is from 2020-12-01T00:00:00Z to 2021-01-01T00:00:00Z.
+pub fn evaluate_range<Tz: chrono::TimeZone>(
+    range_clue: RangeClue,
+    now: DateTime<Tz>,
+) -> Result<(DateTime<Tz>, DateTime<Tz>), EvaluationError> {
+    match range_clue {
+        RangeClue::Last(n, quantifier) => {
+            Ok((calendar_shift(now.clone(), n, &quantifier, false)?, now))
+        }
+        RangeClue::Next(n, quantifier) => {
+            Ok((now.clone(), calendar_shift(now, n, &quantifier, true)?))
+        }
+        RangeClue::PartialISO(year, month) => {
+            let (start, end) = partial_iso_range(year, month)?;
+            let utc = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc();
+            Ok((
+                utc(start).with_timezone(&now.timezone()),
+                utc(end).with_timezone(&now.timezone()),
+            ))
+        }
+    }
+}
+
+/// Coarse human description of `to` relative to `from` e.g. "in 2 hours", "3 days ago".
+///
+/// Uses the largest unit (minutes, hours, days, weeks, months of 30 days) that fits
+/// at least once, rounding down e.g. 90 minutes is "in 1 hour".
+/// Less than a minute is "now".
+pub fn humanize<Tz: chrono::TimeZone>(from: DateTime<Tz>, to: DateTime<Tz>) -> String {
+    let delta = to.signed_duration_since(from);
+    let future = delta > Duration::zero();
+    let minutes = delta.num_minutes().unsigned_abs();
+    let (n, unit) = match minutes {
+        0 => return "now".to_string(),
+        m if m >= 30 * 24 * 60 => (m / (30 * 24 * 60), "month"),
+        m if m >= 7 * 24 * 60 => (m / (7 * 24 * 60), "week"),
+        m if m >= 24 * 60 => (m / (24 * 60), "day"),
+        m if m >= 60 => (m / 60, "hour"),
+        m => (m, "minute"),
+    };
+    let plural = if n > 1 { "s" } else { "" };
+    if future {
+        format!("in {} {}{}", n, unit, plural)
+    } else {
+        format!("{} {}{} ago", n, unit, plural)
+    }
+}
+
+/// Iterator over the occurrences of a `Recurrence`, see `Recurrence::iter_from`.
+pub struct RecurrenceIter<'a, Tz: chrono::TimeZone> {
+    recurrence: &'a Recurrence,
+    now: DateTime<Tz>,
+    index: usize,
+}
+
+impl Recurrence {
+    /// Occurrences of this recurrence strictly after reference time `now`.
+    ///
+    /// * "every <weekday>": upcoming weekdays at 00:00 e.g. "every monday" yields next monday,
+    ///   the monday after...
+    /// * "every <n> <quantifier>": `now` shifted by `n`, `2n`... quantifiers,
+    ///   months are shifted using calendar arithmetic.
+    ///
+    /// A recurrence with an interval of 0 has no occurrences.
+    pub fn iter_from<Tz: chrono::TimeZone>(&self, now: DateTime<Tz>) -> RecurrenceIter<'_, Tz> {
+        RecurrenceIter {
+            recurrence: self,
+            now,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, Tz: chrono::TimeZone> RecurrenceIter<'a, Tz> {
+    fn occurrence(&self, index: usize) -> Result<DateTime<Tz>, EvaluationError> {
+        let intervals = index
+            .checked_mul(self.recurrence.interval)
+            .ok_or(EvaluationError::OutOfRange)?;
+        match &self.recurrence.unit {
+            RecurrenceUnit::Weekday(weekday) => {
+                let days_ahead = (7 + weekday.num_days_from_monday()
+                    - self.now.weekday().num_days_from_monday())
+                    % 7;
+                let days_ahead = if days_ahead == 0 { 7 } else { days_ahead };
+                let date = self.now.date_naive()
+                    + Duration::days(days_ahead as i64)
+                    + Duration::weeks(intervals as i64);
+                at_hms(&self.now, date, (0, 0, 0))
+            }
+            RecurrenceUnit::Quantifier(quantifier) => {
+                let intervals = intervals
+                    .checked_add(self.recurrence.interval)
+                    .ok_or(EvaluationError::OutOfRange)?;
+                calendar_shift(self.now.clone(), intervals, quantifier, true)
+            }
+        }
+    }
+}
+
+impl<'a, Tz: chrono::TimeZone> Iterator for RecurrenceIter<'a, Tz> {
+    type Item = DateTime<Tz>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.recurrence.interval == 0 {
+            return None;
+        }
+        let occurrence = self.occurrence(self.index).ok()?;
+        self.index += 1;
+        Some(occurrence)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::interpreter::{check_hms, evaluate, evaluate_time_clue};
+    use crate::interpreter::{
+        check_hms, current_week_anchor, evaluate, evaluate_range, evaluate_time_clue,
+        evaluate_with_delta, evaluate_with_options, humanize, week_anchor, week_start_monday,
+        BareIntMeaning, ClampTime, ClockHint, EvaluationError, ParseOptions,
+    };
     use crate::parser::AMPM::{AM, PM};
-    use crate::parser::{Modifier, TimeClue};
-    use chrono::offset::TimeZone;
-    use chrono::Utc;
-    use chrono::Weekday;
+    use crate::parser::{
+        parse_range_clue_from_str, parse_recurrence_from_str, parse_time_clue_from_str, Modifier,
+        Quantifier, TimeClue,
+    };
+    use chrono::{
+        DateTime, Datelike, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone,
+        Utc, Weekday,
+    };
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+            .unwrap()
+            .and_utc()
+    }
+
+    /// Central European Time for 2020: UTC+2 from 2020-03-29T01:00Z to 2020-10-25T01:00Z, UTC+1 otherwise.
+    #[derive(Debug, Clone, Copy)]
+    struct Cet2020;
+
+    impl Cet2020 {
+        fn offset_at(instant: &NaiveDateTime) -> FixedOffset {
+            let summer =
+                utc("2020-03-29T01:00:00").naive_utc()..utc("2020-10-25T01:00:00").naive_utc();
+            if summer.contains(instant) {
+                FixedOffset::east_opt(2 * 3600).unwrap()
+            } else {
+                FixedOffset::east_opt(3600).unwrap()
+            }
+        }
+    }
+
+    impl TimeZone for Cet2020 {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Cet2020
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            // earliest (largest offset) first
+            let offsets: Vec<FixedOffset> = [2 * 3600, 3600]
+                .iter()
+                .map(|secs| FixedOffset::east_opt(*secs).unwrap())
+                .filter(|offset| Cet2020::offset_at(&(*local - *offset)) == *offset)
+                .collect();
+            match offsets.as_slice() {
+                [offset] => LocalResult::Single(*offset),
+                [earliest, latest] => LocalResult::Ambiguous(*earliest, *latest),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Cet2020::offset_at(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Cet2020::offset_at(utc)
+        }
+    }
+
+    fn cet(s: &str) -> DateTime<Cet2020> {
+        let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap();
+        Cet2020.from_local_datetime(&naive).earliest().unwrap()
+    }
 
     #[test]
     fn test_check_hms() {
         assert_eq!(check_hms((19, 43, 42), None), Ok((19, 43, 42)));
-        assert_eq!(check_hms((19, 43, 42), Some(AM)), Ok((19, 43, 42)));
+        assert!(check_hms((19, 43, 42), Some(AM)).is_err());
         assert!(check_hms((19, 43, 42), Some(PM)).is_err());
         assert!(check_hms((24, 43, 42), None).is_err());
         assert!(check_hms((19, 63, 42), None).is_err());
@@ -172,12 +953,8 @@ mod test {
 
     #[test]
     fn test_next_weekday() {
-        let now = Utc
-            .datetime_from_str("2020-07-12T12:45:00", "%Y-%m-%dT%H:%M:%S")
-            .unwrap(); // sunday
-        let expected = Utc
-            .datetime_from_str("2020-07-17T00:00:00", "%Y-%m-%dT%H:%M:%S")
-            .unwrap();
+        let now = utc("2020-07-12T12:45:00"); // sunday
+        let expected = utc("2020-07-17T00:00:00");
         assert_eq!(
             evaluate(
                 TimeClue::RelativeDayAt(Modifier::Next, Weekday::Fri, None, None),
@@ -188,26 +965,1237 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_next_last_weekday_policy() {
+        let weekdays = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ];
+        // 2020-07-06 is a monday
+        for day in 6..=12 {
+            let now = utc(&format!("2020-07-{:02}T12:45:00", day));
+            let today = now.date_naive();
+            for weekday in weekdays.iter() {
+                let next = evaluate(
+                    TimeClue::RelativeDayAt(Modifier::Next, *weekday, None, None),
+                    now,
+                )
+                .unwrap();
+                let last = evaluate(
+                    TimeClue::RelativeDayAt(Modifier::Last, *weekday, None, None),
+                    now,
+                )
+                .unwrap();
+                assert_eq!(next.weekday(), *weekday);
+                assert_eq!(last.weekday(), *weekday);
+                // strictly after (before) today, within a week
+                let days_ahead = (next.date_naive() - today).num_days();
+                let days_behind = (today - last.date_naive()).num_days();
+                assert!(
+                    (1..=7).contains(&days_ahead),
+                    "next {} on {}",
+                    weekday,
+                    today
+                );
+                assert!(
+                    (1..=7).contains(&days_behind),
+                    "last {} on {}",
+                    weekday,
+                    today
+                );
+                if *weekday == now.weekday() {
+                    assert_eq!(days_ahead, 7);
+                    assert_eq!(days_behind, 7);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decades_centuries() {
+        let eval = |s, now| evaluate(parse_time_clue_from_str(s).unwrap(), utc(now)).unwrap();
+        assert_eq!(
+            eval("2 decades ago", "2020-07-12T12:45:00"),
+            utc("2000-07-12T12:45:00")
+        );
+        assert_eq!(
+            eval("a century ago", "2020-07-12T12:45:00"),
+            utc("1920-07-12T12:45:00")
+        );
+        assert_eq!(
+            eval("in 3 centuries", "2020-07-12T12:45:00"),
+            utc("2320-07-12T12:45:00")
+        );
+        // leap days are clamped to the end of february of non leap years
+        assert_eq!(
+            eval("2 decades ago", "2020-02-29T12:45:00"),
+            utc("2000-02-29T12:45:00")
+        );
+        assert_eq!(
+            eval("a decade ago", "2016-02-29T12:45:00"),
+            utc("2006-02-28T12:45:00")
+        );
+        assert_eq!(
+            eval("a century ago", "2000-02-29T12:45:00"),
+            utc("1900-02-28T12:45:00")
+        );
+        let now = utc("2020-07-12T12:45:00");
+        assert_eq!(
+            evaluate(TimeClue::Relative(usize::MAX, Quantifier::Centuries), now),
+            Err(EvaluationError::OutOfRange)
+        );
+        assert_eq!(
+            evaluate(TimeClue::Relative(10_000, Quantifier::Centuries), now),
+            Err(EvaluationError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_huge_relative_out_of_range() {
+        let now = utc("2020-07-12T12:45:00");
+        let calendar_weeks = ParseOptions {
+            calendar_weeks: true,
+            ..Default::default()
+        };
+        for s in [
+            "in 99999999999 days",
+            "99999999999 days ago",
+            "in 2147483647 months",
+            "999999999999999 weeks ago",
+            "in 99999999999999999 min",
+        ]
+        .iter()
+        {
+            let time_clue = parse_time_clue_from_str(s).unwrap();
+            assert_eq!(
+                evaluate(time_clue.clone(), now),
+                Err(EvaluationError::OutOfRange),
+                "{}",
+                s
+            );
+            assert_eq!(
+                evaluate_with_options(time_clue, now, &calendar_weeks),
+                Err(EvaluationError::OutOfRange),
+                "{}",
+                s
+            );
+        }
+        assert_eq!(
+            evaluate(TimeClue::RelativeFuture(usize::MAX, Quantifier::Min), now),
+            Err(EvaluationError::OutOfRange)
+        );
+    }
+
     #[test]
     fn test_assume_next_day() {
-        let now = Utc
-            .datetime_from_str("2020-07-12T12:45:00", "%Y-%m-%dT%H:%M:%S")
-            .unwrap(); // sunday
+        let now = utc("2020-07-12T12:45:00"); // sunday
 
-        let expected = Utc
-            .datetime_from_str("2020-07-12T08:00:00", "%Y-%m-%dT%H:%M:%S")
-            .unwrap();
+        let expected = utc("2020-07-12T08:00:00");
         assert_eq!(
-            evaluate_time_clue(TimeClue::Time((8, 0, 0), None), now.clone(), false).unwrap(),
+            evaluate_time_clue(TimeClue::Time((8, 0, 0), None), now, false).unwrap(),
             expected
         );
 
-        let expected = Utc
-            .datetime_from_str("2020-07-13T08:00:00", "%Y-%m-%dT%H:%M:%S")
-            .unwrap();
+        let expected = utc("2020-07-13T08:00:00");
         assert_eq!(
             evaluate_time_clue(TimeClue::Time((8, 0, 0), None), now, true).unwrap(),
             expected
         );
     }
+
+    #[test]
+    fn test_assume_next_day_am_pm() {
+        for (now, s, expected) in [
+            ("2020-07-12T20:00:00", "7pm", "2020-07-13T19:00:00"),
+            ("2020-07-12T18:59:59", "7pm", "2020-07-12T19:00:00"),
+            ("2020-07-12T19:00:00", "7pm", "2020-07-12T19:00:00"),
+            ("2020-07-12T06:00:00", "7pm", "2020-07-12T19:00:00"),
+            ("2020-07-12T20:00:00", "7am", "2020-07-13T07:00:00"),
+            ("2020-07-12T07:30:00", "7am", "2020-07-13T07:00:00"),
+            ("2020-07-12T06:00:00", "7am", "2020-07-12T07:00:00"),
+            ("2020-07-12T00:30:00", "12am", "2020-07-13T00:00:00"),
+            ("2020-07-12T12:30:00", "12pm", "2020-07-13T12:00:00"),
+            ("2020-07-12T11:30:00", "12pm", "2020-07-12T12:00:00"),
+        ]
+        .iter()
+        {
+            let time_clue = parse_time_clue_from_str(s).unwrap();
+            assert_eq!(
+                evaluate_time_clue(time_clue, utc(now), true).unwrap(),
+                utc(expected),
+                "{} at {}",
+                s,
+                now
+            );
+        }
+    }
+
+    #[test]
+    fn test_midnight() {
+        let now = utc("2020-07-12T12:45:00");
+
+        let expected = utc("2020-07-13T00:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Midnight, now, &ParseOptions::default()).unwrap(),
+            expected
+        );
+
+        let expected = utc("2020-07-12T00:00:00");
+        let options = ParseOptions {
+            strict_midnight: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            evaluate_with_options(TimeClue::Midnight, now, &options).unwrap(),
+            expected
+        );
+
+        // "12 midnight" is the same as "midnight"
+        for s in ["midnight", "12 midnight"].iter() {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc("2020-07-13T00:00:00")
+            );
+        }
+    }
+
+    #[test]
+    fn test_dotted_date() {
+        let now = utc("2020-07-12T12:45:00");
+        let expected = utc("2020-12-25T00:00:00");
+        let time_clue = parse_time_clue_from_str("25.12.2020").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_evaluate_with_delta() {
+        let now = utc("2020-07-12T12:45:00");
+        let options = ParseOptions::default();
+
+        let expected = utc("2020-07-12T10:45:00");
+        assert_eq!(
+            evaluate_with_delta(TimeClue::Relative(2, Quantifier::Hours), now, &options).unwrap(),
+            (expected, Some(Duration::hours(2)))
+        );
+
+        let expected = utc("2020-07-14T12:45:00");
+        assert_eq!(
+            evaluate_with_delta(TimeClue::RelativeFuture(2, Quantifier::Days), now, &options)
+                .unwrap(),
+            (expected, Some(Duration::days(2)))
+        );
+
+        let expected = utc("2020-07-12T08:00:00");
+        assert_eq!(
+            evaluate_with_delta(TimeClue::Time((8, 0, 0), None), now, &options).unwrap(),
+            (expected, None)
+        );
+
+        // calendar shifts: 25 leap years between 1920 and 2020
+        let expected = utc("1920-07-12T12:45:00");
+        assert_eq!(
+            evaluate_with_delta(TimeClue::Relative(1, Quantifier::Centuries), now, &options)
+                .unwrap(),
+            (expected, Some(Duration::days(100 * 365 + 25)))
+        );
+    }
+
+    #[test]
+    fn test_hour_24() {
+        let now = utc("2020-07-12T12:45:00");
+        let options = ParseOptions {
+            allow_hour_24: true,
+            ..Default::default()
+        };
+
+        let expected = utc("2020-07-13T00:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((24, 0, 0), None), now, &options).unwrap(),
+            expected
+        );
+        let expected = utc("2020-12-26T00:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::ISO((2020, 12, 25), (24, 0, 0)), now, &options)
+                .unwrap(),
+            expected
+        );
+        assert!(evaluate_with_options(TimeClue::Time((24, 30, 0), None), now, &options).is_err());
+        assert!(
+            evaluate_with_options(TimeClue::Time((24, 0, 0), Some(PM)), now, &options).is_err()
+        );
+
+        assert!(evaluate(TimeClue::Time((24, 0, 0), None), now).is_err());
+        assert!(evaluate(TimeClue::ISO((2020, 12, 25), (24, 0, 0)), now).is_err());
+    }
+
+    #[test]
+    fn test_with_offset() {
+        let paris = FixedOffset::east_opt(2 * 3600).unwrap();
+        let now = utc("2020-07-12T12:45:00").with_timezone(&paris);
+
+        let expected = utc("2020-07-13T09:00:00");
+        let time_clue = parse_time_clue_from_str("tomorrow at 9 UTC").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+
+        let expected = utc("2020-07-13T07:00:00");
+        let time_clue = parse_time_clue_from_str("tomorrow at 9am +0200").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+
+        let expected = utc("2020-07-10T22:00:00");
+        let time_clue = parse_time_clue_from_str("friday 17:00 -05:00").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_clamp_time() {
+        let now = utc("2020-07-12T12:45:00");
+
+        let options = ParseOptions {
+            clamp_time: ClampTime::MinutesSeconds,
+            ..Default::default()
+        };
+        let expected = utc("2020-07-12T20:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((19, 60, 0), None), now, &options).unwrap(),
+            expected
+        );
+        let expected = utc("2020-07-12T19:44:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((19, 43, 60), None), now, &options).unwrap(),
+            expected
+        );
+        let expected = utc("2020-07-12T20:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((7, 59, 60), Some(PM)), now, &options).unwrap(),
+            expected
+        );
+        assert!(evaluate_with_options(TimeClue::Time((23, 60, 0), None), now, &options).is_err());
+        assert!(evaluate_with_options(TimeClue::Time((24, 0, 0), None), now, &options).is_err());
+
+        let options = ParseOptions {
+            clamp_time: ClampTime::Hours,
+            ..Default::default()
+        };
+        let expected = utc("2020-07-13T00:00:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((23, 60, 0), None), now, &options).unwrap(),
+            expected
+        );
+        let expected = utc("2020-07-13T01:30:00");
+        assert_eq!(
+            evaluate_with_options(TimeClue::Time((25, 30, 0), None), now, &options).unwrap(),
+            expected
+        );
+
+        assert!(evaluate(TimeClue::Time((19, 60, 0), None), now).is_err());
+        assert!(evaluate(TimeClue::Time((19, 43, 60), None), now).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_range() {
+        let now = utc("2020-07-12T12:45:00");
+
+        let range_clue = parse_range_clue_from_str("last 7 days").unwrap();
+        assert_eq!(
+            evaluate_range(range_clue, now).unwrap(),
+            (utc("2020-07-05T12:45:00"), now)
+        );
+
+        let range_clue = parse_range_clue_from_str("next 3 weeks").unwrap();
+        assert_eq!(
+            evaluate_range(range_clue, now).unwrap(),
+            (now, utc("2020-08-02T12:45:00"))
+        );
+
+        let now = utc("2020-03-31T12:00:00");
+        let range_clue = parse_range_clue_from_str("last month").unwrap();
+        assert_eq!(
+            evaluate_range(range_clue, now).unwrap(),
+            (utc("2020-02-29T12:00:00"), now)
+        );
+
+        let now = utc("2020-12-31T12:00:00");
+        let range_clue = parse_range_clue_from_str("next 2 months").unwrap();
+        assert_eq!(
+            evaluate_range(range_clue, now).unwrap(),
+            (now, utc("2021-02-28T12:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_12_hour_clock() {
+        assert_eq!(check_hms((1, 0, 0), Some(PM)), Ok((13, 0, 0)));
+        assert_eq!(check_hms((1, 0, 0), Some(AM)), Ok((1, 0, 0)));
+        assert_eq!(check_hms((12, 0, 0), Some(AM)), Ok((0, 0, 0)));
+        assert_eq!(check_hms((12, 30, 0), Some(PM)), Ok((12, 30, 0)));
+        assert_eq!(
+            check_hms((13, 0, 0), Some(PM)),
+            Err(EvaluationError::InvalidTimeAMPM {
+                hour: 13,
+                minute: 0,
+                second: 0,
+                am_or_pm: PM
+            })
+        );
+        assert!(check_hms((13, 0, 0), Some(AM)).is_err());
+        assert!(check_hms((0, 0, 0), Some(AM)).is_err());
+        assert!(check_hms((0, 0, 0), Some(PM)).is_err());
+
+        let now = utc("2020-07-12T12:45:00"); // sunday
+        let expected = utc("2020-07-10T13:00:00");
+        let time_clue = parse_time_clue_from_str("friday at 1 pm").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+        let time_clue = parse_time_clue_from_str("friday at 13 pm").unwrap();
+        assert!(evaluate(time_clue, now).is_err());
+    }
+
+    #[test]
+    fn test_recurrence_iter() {
+        let now = utc("2020-07-12T12:45:00"); // sunday
+        let recurrence = parse_recurrence_from_str("every monday").unwrap().unwrap();
+        let occurrences: Vec<DateTime<Utc>> = recurrence.iter_from(now).take(3).collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                utc("2020-07-13T00:00:00"),
+                utc("2020-07-20T00:00:00"),
+                utc("2020-07-27T00:00:00"),
+            ]
+        );
+
+        let now = utc("2020-07-13T12:45:00"); // monday
+        let occurrences: Vec<DateTime<Utc>> = recurrence.iter_from(now).take(2).collect();
+        assert_eq!(
+            occurrences,
+            vec![utc("2020-07-20T00:00:00"), utc("2020-07-27T00:00:00")]
+        );
+
+        let recurrence = parse_recurrence_from_str("every 2 weeks").unwrap().unwrap();
+        let occurrences: Vec<DateTime<Utc>> = recurrence.iter_from(now).take(3).collect();
+        assert_eq!(
+            occurrences,
+            vec![
+                utc("2020-07-27T12:45:00"),
+                utc("2020-08-10T12:45:00"),
+                utc("2020-08-24T12:45:00"),
+            ]
+        );
+
+        let recurrence = parse_recurrence_from_str("every day").unwrap().unwrap();
+        let occurrences: Vec<DateTime<Utc>> = recurrence.iter_from(now).take(2).collect();
+        assert_eq!(
+            occurrences,
+            vec![utc("2020-07-14T12:45:00"), utc("2020-07-15T12:45:00")]
+        );
+
+        let now = utc("2020-01-31T12:00:00");
+        let recurrence = parse_recurrence_from_str("every month").unwrap().unwrap();
+        let occurrences: Vec<DateTime<Utc>> = recurrence.iter_from(now).take(2).collect();
+        assert_eq!(
+            occurrences,
+            vec![utc("2020-02-29T12:00:00"), utc("2020-03-31T12:00:00")]
+        );
+
+        let recurrence = parse_recurrence_from_str("every 0 days").unwrap().unwrap();
+        assert_eq!(recurrence.iter_from(now).next(), None);
+    }
+
+    #[test]
+    fn test_pm_conversion() {
+        let now = utc("2020-07-12T08:45:00"); // sunday
+        let times = [
+            ("7:30:15 pm", "2020-07-12T19:30:15"),
+            ("7:30 pm", "2020-07-12T19:30:00"),
+            ("7 pm", "2020-07-12T19:00:00"),
+            ("7 p.m.", "2020-07-12T19:00:00"),
+            ("friday at 7:30:15 pm", "2020-07-10T19:30:15"),
+            ("tomorrow at 7:30 PM", "2020-07-13T19:30:00"),
+        ];
+        for (s, expected) in times.iter() {
+            let time_clue = parse_time_clue_from_str(s).unwrap();
+            assert_eq!(evaluate(time_clue, now).unwrap(), utc(expected));
+        }
+    }
+
+    #[test]
+    fn test_day_out_of_range_for_month() {
+        let now = utc("2020-07-12T12:45:00");
+        let eval = |s| evaluate(parse_time_clue_from_str(s).unwrap(), now);
+        for (s, (year, month, day)) in [
+            ("2020-02-30", (2020, 2, 30)),
+            ("2020-04-31T12:00", (2020, 4, 31)),
+            ("2021-02-29", (2021, 2, 29)),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                eval(s),
+                Err(EvaluationError::DayOutOfRangeForMonth {
+                    year: *year,
+                    month: *month,
+                    day: *day
+                }),
+                "{}",
+                s
+            );
+        }
+        assert_eq!(eval("2020-02-29").unwrap(), utc("2020-02-29T00:00:00"));
+        assert_eq!(
+            eval("2021-02-29").unwrap_err().to_string(),
+            "invalid date: 2021-02 has no day 29"
+        );
+        // invalid months and days are not days out of range for a month
+        assert!(matches!(
+            eval("2020-13-01"),
+            Err(EvaluationError::ChronoISOError { .. })
+        ));
+        assert!(matches!(
+            eval("2020-02-00"),
+            Err(EvaluationError::ChronoISOError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_signed_years() {
+        let now = utc("2020-07-12T12:45:00");
+        let eval = |s| evaluate(parse_time_clue_from_str(s).unwrap(), now);
+        let year_1 = NaiveDate::from_ymd_opt(1, 1, 1).unwrap();
+        assert_eq!(eval("0001-01-01").unwrap().date_naive(), year_1);
+        let year_0 = NaiveDate::from_ymd_opt(0, 1, 1).unwrap();
+        assert_eq!(eval("0000-01-01").unwrap().date_naive(), year_0);
+        assert_eq!(eval("0000").unwrap().date_naive(), year_0);
+        let ides = NaiveDate::from_ymd_opt(-44, 3, 15).unwrap();
+        assert_eq!(eval("-0044-03-15").unwrap().date_naive(), ides);
+        assert_eq!(
+            eval("-0044-075").unwrap().date_naive(),
+            ides,
+            "-44 is a leap year"
+        );
+        for s in ["-300000-01-01", "+300000-01-01", "-300000", "+300000-001"].iter() {
+            assert_eq!(eval(s), Err(EvaluationError::OutOfRange), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_ordinal_date() {
+        let now = utc("2020-07-12T12:45:00");
+        let expected = utc("2020-12-31T00:00:00");
+        let time_clue = parse_time_clue_from_str("2020-366").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+
+        let expected = utc("2020-12-25T00:00:00");
+        let time_clue = parse_time_clue_from_str("2020-360").unwrap();
+        assert_eq!(evaluate(time_clue, now).unwrap(), expected);
+
+        let time_clue = parse_time_clue_from_str("2021-366").unwrap();
+        assert_eq!(
+            evaluate(time_clue, now),
+            Err(EvaluationError::InvalidOrdinalDate {
+                year: 2021,
+                day: 366
+            })
+        );
+        let time_clue = parse_time_clue_from_str("2020-000").unwrap();
+        assert!(evaluate(time_clue, now).is_err());
+    }
+
+    #[test]
+    fn test_week_anchor() {
+        // 2020-07-06 is a monday
+        for day in 6..=12 {
+            let now = utc(&format!("2020-07-{:02}T12:45:00", day));
+            assert_eq!(
+                week_anchor(now, Weekday::Mon).unwrap(),
+                utc("2020-07-06T00:00:00")
+            );
+            assert_eq!(
+                week_anchor(now, Weekday::Sun).unwrap(),
+                if day == 12 {
+                    utc("2020-07-12T00:00:00")
+                } else {
+                    utc("2020-07-05T00:00:00")
+                }
+            );
+            assert_eq!(week_start_monday(now).unwrap(), utc("2020-07-06T00:00:00"));
+        }
+    }
+
+    #[test]
+    fn test_current_week_anchor() {
+        let date = |d| NaiveDate::from_ymd_opt(2020, 7, d).unwrap();
+        // 2020-07-06 is a monday
+        for day in 6..=12 {
+            assert_eq!(current_week_anchor(date(day), Weekday::Mon), date(6));
+            assert_eq!(
+                current_week_anchor(date(day), date(day).weekday()),
+                date(day)
+            );
+        }
+        assert_eq!(current_week_anchor(date(8), Weekday::Thu), date(2));
+        // across a year boundary
+        let new_year = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        assert_eq!(
+            current_week_anchor(new_year, Weekday::Mon),
+            NaiveDate::from_ymd_opt(2020, 12, 28).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_calendar_weeks() {
+        let calendar_weeks = ParseOptions {
+            calendar_weeks: true,
+            ..Default::default()
+        };
+        // DST starts on 2020-03-29 in between
+        let now = cet("2020-03-25T12:00:00");
+        let in_a_week = || parse_time_clue_from_str("in 1 week").unwrap();
+        assert_eq!(
+            evaluate_with_options(in_a_week(), now, &calendar_weeks).unwrap(),
+            cet("2020-04-01T12:00:00")
+        );
+        assert_eq!(
+            evaluate_with_delta(in_a_week(), now, &calendar_weeks).unwrap(),
+            (
+                cet("2020-04-01T12:00:00"),
+                Some(Duration::weeks(1) - Duration::hours(1))
+            )
+        );
+        assert_eq!(
+            evaluate_with_options(in_a_week(), now, &ParseOptions::default()).unwrap(),
+            cet("2020-04-01T13:00:00")
+        );
+
+        // DST ends on 2020-10-25 in between
+        let now = cet("2020-10-28T12:00:00");
+        let weeks_ago = || parse_time_clue_from_str("2 weeks ago").unwrap();
+        assert_eq!(
+            evaluate_with_options(weeks_ago(), now, &calendar_weeks).unwrap(),
+            cet("2020-10-14T12:00:00")
+        );
+        assert_eq!(
+            evaluate_with_options(weeks_ago(), now, &ParseOptions::default()).unwrap(),
+            cet("2020-10-14T13:00:00")
+        );
+
+        // 02:30 does not exist on 2020-03-29
+        let now = cet("2020-03-22T02:30:00");
+        assert_eq!(
+            evaluate_with_options(
+                TimeClue::RelativeFuture(1, Quantifier::Weeks),
+                now,
+                &calendar_weeks
+            ),
+            Err(EvaluationError::NonExistentLocalTime(
+                NaiveDateTime::parse_from_str("2020-03-29T02:30:00", "%Y-%m-%dT%H:%M:%S").unwrap()
+            ))
+        );
+
+        // other quantifiers are unaffected
+        let now = cet("2020-03-28T12:00:00");
+        assert_eq!(
+            evaluate_with_options(
+                TimeClue::RelativeFuture(1, Quantifier::Days),
+                now,
+                &calendar_weeks
+            )
+            .unwrap(),
+            cet("2020-03-29T13:00:00")
+        );
+    }
+
+    #[test]
+    fn test_weekday_date() {
+        let now = utc("2020-07-12T12:45:00");
+        for s in [
+            "friday 2020-12-25",
+            "fri, 2020-12-25",
+            "friday 25/12/2020",
+            "fri 2020-12-25T00:00",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc("2020-12-25T00:00:00")
+            );
+        }
+        assert_eq!(
+            evaluate(
+                parse_time_clue_from_str("thursday 2020-12-25").unwrap(),
+                now
+            ),
+            Err(EvaluationError::WeekdayDateMismatch {
+                weekday: Weekday::Thu,
+                date: NaiveDate::from_ymd_opt(2020, 12, 25).unwrap(),
+            })
+        );
+        assert!(matches!(
+            evaluate(parse_time_clue_from_str("friday 2020-02-30").unwrap(), now),
+            Err(EvaluationError::DayOutOfRangeForMonth { .. })
+        ));
+    }
+
+    #[test]
+    fn test_humanize() {
+        let now = utc("2020-07-12T12:45:00");
+        for (delta, expected) in [
+            (Duration::seconds(30), "now"),
+            (Duration::minutes(1), "in 1 minute"),
+            (Duration::minutes(-45), "45 minutes ago"),
+            (Duration::minutes(90), "in 1 hour"),
+            (Duration::hours(50), "in 2 days"),
+            (Duration::days(-3), "3 days ago"),
+            (Duration::days(15), "in 2 weeks"),
+            (Duration::days(-65), "2 months ago"),
+        ]
+        .iter()
+        {
+            assert_eq!(humanize(now, now + *delta), *expected);
+        }
+    }
+
+    #[test]
+    fn test_week_of_month() {
+        let now = utc("2020-07-12T12:45:00");
+        for (s, expected) in [
+            // 2020-12-01 is a tuesday
+            ("first week of december", "2020-11-30T00:00:00"),
+            ("second week of december", "2020-12-07T00:00:00"),
+            ("last week of december", "2020-12-28T00:00:00"),
+            // 2020-07-31 is a friday
+            ("last week of this month", "2020-07-27T00:00:00"),
+            ("first week of next month", "2020-07-27T00:00:00"),
+            ("the last week of last month", "2020-06-29T00:00:00"),
+            ("fifth week of july", "2020-07-27T00:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc(expected)
+            );
+        }
+        // 2021-02-01 is a monday, february 2021 has 4 weeks
+        let now = utc("2021-02-12T12:45:00");
+        assert_eq!(
+            evaluate(
+                parse_time_clue_from_str("fifth week of february").unwrap(),
+                now
+            ),
+            Err(EvaluationError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_clone_time_clue() {
+        let now = utc("2020-07-12T20:00:00");
+        let time_clue = parse_time_clue_from_str("19:43").unwrap();
+        let assume_next_day = ParseOptions {
+            assume_next_day: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            evaluate_with_options(time_clue.clone(), now, &ParseOptions::default()).unwrap(),
+            utc("2020-07-12T19:43:00")
+        );
+        assert_eq!(
+            evaluate_with_options(time_clue, now, &assume_next_day).unwrap(),
+            utc("2020-07-13T19:43:00")
+        );
+    }
+
+    #[test]
+    fn test_period_offset() {
+        for (now, s, expected) in [
+            // january has 31 days
+            (
+                "2021-01-12T12:45:00",
+                "3 days before end of month",
+                "2021-01-28T00:00:00",
+            ),
+            // february 2021 has 28 days
+            (
+                "2021-02-12T12:45:00",
+                "3 days before end of month",
+                "2021-02-25T00:00:00",
+            ),
+            ("2021-02-12T12:45:00", "end of month", "2021-02-28T00:00:00"),
+            (
+                "2021-02-12T12:45:00",
+                "2 days after start of month",
+                "2021-02-03T00:00:00",
+            ),
+            (
+                "2021-02-12T12:45:00",
+                "1 month before end of year",
+                "2021-11-30T00:00:00",
+            ),
+            // 2021-02-12 is a friday
+            (
+                "2021-02-12T12:45:00",
+                "start of the week",
+                "2021-02-08T00:00:00",
+            ),
+            (
+                "2021-02-12T12:45:00",
+                "12 hours after end of week",
+                "2021-02-14T12:00:00",
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), utc(now)).unwrap(),
+                utc(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_shortcut_day_dst() {
+        // DST starts on 2020-03-29 at 02:00, the day is 23 hours long
+        let now = cet("2020-03-29T12:00:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("yesterday at 12").unwrap(), now).unwrap(),
+            cet("2020-03-28T12:00:00")
+        );
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("tomorrow at 12").unwrap(), now).unwrap(),
+            cet("2020-03-30T12:00:00")
+        );
+        let now = cet("2020-03-28T12:00:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("tomorrow at 9").unwrap(), now).unwrap(),
+            cet("2020-03-29T09:00:00")
+        );
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("tomorrow at 2:30").unwrap(), now),
+            Err(EvaluationError::NonExistentLocalTime(
+                NaiveDateTime::parse_from_str("2020-03-29T02:30:00", "%Y-%m-%dT%H:%M:%S").unwrap()
+            ))
+        );
+
+        // DST ends on 2020-10-25 at 03:00, the day is 25 hours long
+        let now = cet("2020-10-26T00:30:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("yesterday").unwrap(), now).unwrap(),
+            cet("2020-10-25T00:00:00")
+        );
+        let now = cet("2020-10-24T12:00:00");
+        let tomorrow =
+            evaluate(parse_time_clue_from_str("tomorrow at 2:30").unwrap(), now).unwrap();
+        // ambiguous local time resolves to the earliest one, still in summer time
+        assert_eq!(tomorrow, utc("2020-10-25T00:30:00"));
+    }
+
+    #[test]
+    fn test_week_from_weekday() {
+        // monday: next friday is 2020-07-10
+        let now = utc("2020-07-06T12:45:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("a week from friday").unwrap(), now).unwrap(),
+            utc("2020-07-17T00:00:00")
+        );
+        // friday: next friday is 2020-07-17
+        let now = utc("2020-07-10T12:45:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("a week on friday").unwrap(), now).unwrap(),
+            utc("2020-07-24T00:00:00")
+        );
+    }
+
+    #[test]
+    fn test_date_offset() {
+        let now = utc("2020-07-12T12:45:00");
+        for (s, expected) in [
+            ("2020-12-25 + 3 days", "2020-12-28T00:00:00"),
+            ("2020-12-25 - 1 week", "2020-12-18T00:00:00"),
+            ("2020-12-30 + 3 days", "2021-01-02T00:00:00"),
+            ("2020-01-31 + 1 month", "2020-02-29T00:00:00"),
+            ("2020-03-01T12:00 - 13 hours", "2020-02-29T23:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_date_anchor_offset() {
+        let now = utc("2020-07-12T12:45:00");
+        for (s, expected) in [
+            ("2 days before 2020-12-25", "2020-12-23T00:00:00"),
+            ("3 hours after 2020-12-25T10:00:00", "2020-12-25T13:00:00"),
+            ("a week after 25/12/2020", "2021-01-01T00:00:00"),
+            ("1 month before 2020-03-31", "2020-02-29T00:00:00"),
+            ("2 days after 2020-360", "2020-12-27T00:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc(expected),
+                "{}",
+                s
+            );
+        }
+        // the anchor is evaluated first
+        assert_eq!(
+            evaluate(
+                parse_time_clue_from_str("2 days before 2021-02-29").unwrap(),
+                now
+            ),
+            Err(EvaluationError::DayOutOfRangeForMonth {
+                year: 2021,
+                month: 2,
+                day: 29
+            })
+        );
+    }
+
+    #[test]
+    fn test_bare_int_meaning() {
+        let now = utc("2020-06-12T12:45:00");
+        let day_of_month = ParseOptions {
+            bare_int: BareIntMeaning::DayOfMonth,
+            ..Default::default()
+        };
+        let fifteen = || parse_time_clue_from_str("15").unwrap();
+        assert_eq!(
+            evaluate_with_options(fifteen(), now, &ParseOptions::default()).unwrap(),
+            utc("2020-06-12T15:00:00")
+        );
+        assert_eq!(
+            evaluate_with_options(fifteen(), now, &day_of_month).unwrap(),
+            utc("2020-06-15T00:00:00")
+        );
+        // not a day
+        for (s, expected) in [
+            ("3pm", "2020-06-12T15:00:00"),
+            ("15:00", "2020-06-12T15:00:00"),
+            ("15:30", "2020-06-12T15:30:00"),
+            ("noon", "2020-06-12T12:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate_with_options(parse_time_clue_from_str(s).unwrap(), now, &day_of_month)
+                    .unwrap(),
+                utc(expected)
+            );
+        }
+        // june has 30 days
+        assert!(
+            evaluate_with_options(parse_time_clue_from_str("31").unwrap(), now, &day_of_month)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_nth_weekday() {
+        // 2020-07-06 is a monday
+        for (now, s, expected) in [
+            (
+                "2020-07-06T12:45:00",
+                "1 monday from now",
+                "2020-07-13T00:00:00",
+            ),
+            (
+                "2020-07-06T12:45:00",
+                "3 mondays from now",
+                "2020-07-27T00:00:00",
+            ),
+            (
+                "2020-07-08T12:45:00",
+                "1 monday from now",
+                "2020-07-13T00:00:00",
+            ),
+            (
+                "2020-07-08T12:45:00",
+                "3 mondays from now",
+                "2020-07-27T00:00:00",
+            ),
+            (
+                "2020-07-12T12:45:00",
+                "1 monday from now",
+                "2020-07-13T00:00:00",
+            ),
+            (
+                "2020-07-12T12:45:00",
+                "3 mondays later",
+                "2020-07-27T00:00:00",
+            ),
+            (
+                "2020-07-08T12:45:00",
+                "1 friday from now",
+                "2020-07-10T00:00:00",
+            ),
+            (
+                "2020-07-08T12:45:00",
+                "2 fridays ago",
+                "2020-06-26T00:00:00",
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), utc(now)).unwrap(),
+                utc(expected)
+            );
+        }
+        assert_eq!(
+            evaluate(
+                parse_time_clue_from_str("0 mondays from now").unwrap(),
+                utc("2020-07-06T12:45:00")
+            ),
+            Err(EvaluationError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_clock_hint() {
+        let nearest = ParseOptions {
+            clock_hint: ClockHint::NearestToNow,
+            ..Default::default()
+        };
+        let now = utc("2020-07-12T18:00:00");
+        for (s, expected) in [
+            ("7", "2020-07-12T19:00:00"),
+            ("7:30", "2020-07-12T19:30:00"),
+            ("5", "2020-07-12T17:00:00"),
+            ("7am", "2020-07-12T07:00:00"),
+            ("13", "2020-07-12T13:00:00"),
+            ("12", "2020-07-12T12:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate_with_options(parse_time_clue_from_str(s).unwrap(), now, &nearest).unwrap(),
+                utc(expected)
+            );
+        }
+        assert_eq!(
+            evaluate_with_options(
+                parse_time_clue_from_str("7").unwrap(),
+                now,
+                &ParseOptions::default()
+            )
+            .unwrap(),
+            utc("2020-07-12T07:00:00")
+        );
+        let now = utc("2020-07-12T03:00:00");
+        assert_eq!(
+            evaluate_with_options(parse_time_clue_from_str("7").unwrap(), now, &nearest).unwrap(),
+            utc("2020-07-12T07:00:00")
+        );
+        // "12" is midnight at 03:00
+        assert_eq!(
+            evaluate_with_options(parse_time_clue_from_str("12").unwrap(), now, &nearest).unwrap(),
+            utc("2020-07-12T00:00:00")
+        );
+        // but "noon" is always noon
+        for s in ["noon", "12 noon"].iter() {
+            assert_eq!(
+                evaluate_with_options(parse_time_clue_from_str(s).unwrap(), now, &nearest).unwrap(),
+                utc("2020-07-12T12:00:00")
+            );
+        }
+    }
+
+    #[test]
+    fn test_shortcut_day_week() {
+        let now = utc("2020-07-12T12:45:00");
+        for (s, expected) in [
+            ("tomorrow week", "2020-07-20T00:00:00"),
+            ("tomorrow week at 9", "2020-07-20T09:00:00"),
+            ("yesterday week", "2020-07-04T00:00:00"),
+            ("yesterday week at 17:30", "2020-07-04T17:30:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict() {
+        let now = utc("2020-07-12T12:45:00");
+        let strict = ParseOptions {
+            strict: true,
+            ..Default::default()
+        };
+        for (s, expected) in [
+            (
+                "30 months ago",
+                EvaluationError::ExtremeMagnitude {
+                    n: 30,
+                    quantifier: Quantifier::Months,
+                },
+            ),
+            (
+                "in 400 days",
+                EvaluationError::ExtremeMagnitude {
+                    n: 400,
+                    quantifier: Quantifier::Days,
+                },
+            ),
+            (
+                "60 weeks before end of year",
+                EvaluationError::ExtremeMagnitude {
+                    n: 60,
+                    quantifier: Quantifier::Weeks,
+                },
+            ),
+            (
+                "0 days ago",
+                EvaluationError::ZeroMagnitude(Quantifier::Days),
+            ),
+            ("in 0 min", EvaluationError::ZeroMagnitude(Quantifier::Min)),
+        ]
+        .iter()
+        {
+            let time_clue = || parse_time_clue_from_str(s).unwrap();
+            assert_eq!(
+                evaluate_with_options(time_clue(), now, &strict).unwrap_err(),
+                *expected
+            );
+            assert!(evaluate_with_options(time_clue(), now, &ParseOptions::default()).is_ok());
+        }
+        for s in ["12 months ago", "in 2 hours", "friday at 9", "2020-12-25"].iter() {
+            assert!(
+                evaluate_with_options(parse_time_clue_from_str(s).unwrap(), now, &strict).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_partial_iso() {
+        let now = utc("2020-07-12T12:45:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("2020").unwrap(), now).unwrap(),
+            utc("2020-01-01T00:00:00")
+        );
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("2020-12").unwrap(), now).unwrap(),
+            utc("2020-12-01T00:00:00")
+        );
+        assert_eq!(
+            evaluate_range(parse_range_clue_from_str("2020").unwrap(), now).unwrap(),
+            (utc("2020-01-01T00:00:00"), utc("2021-01-01T00:00:00"))
+        );
+        assert_eq!(
+            evaluate_range(parse_range_clue_from_str("2020-12").unwrap(), now).unwrap(),
+            (utc("2020-12-01T00:00:00"), utc("2021-01-01T00:00:00"))
+        );
+        assert_eq!(
+            evaluate_range(parse_range_clue_from_str("2020-02").unwrap(), now).unwrap(),
+            (utc("2020-02-01T00:00:00"), utc("2020-03-01T00:00:00"))
+        );
+        assert!(evaluate(parse_time_clue_from_str("2020-13").unwrap(), now).is_err());
+    }
+
+    #[test]
+    fn test_within_range() {
+        let now = utc("2020-07-12T12:45:00");
+        assert_eq!(
+            evaluate_range(parse_range_clue_from_str("in the next hour").unwrap(), now).unwrap(),
+            (now, utc("2020-07-12T13:45:00"))
+        );
+        assert_eq!(
+            evaluate_range(parse_range_clue_from_str("within 2 days").unwrap(), now).unwrap(),
+            (now, utc("2020-07-14T12:45:00"))
+        );
+    }
+
+    #[test]
+    fn test_day_of_month() {
+        let now = utc("2020-06-12T12:45:00");
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("the 3rd").unwrap(), now).unwrap(),
+            utc("2020-06-03T00:00:00")
+        );
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("the 22nd at 5pm").unwrap(), now).unwrap(),
+            utc("2020-06-22T17:00:00")
+        );
+        // june has 30 days
+        assert_eq!(
+            evaluate(parse_time_clue_from_str("the 31st").unwrap(), now),
+            Err(EvaluationError::DayOutOfRangeForMonth {
+                year: 2020,
+                month: 6,
+                day: 31
+            })
+        );
+    }
+
+    #[test]
+    fn test_oclock() {
+        let now = utc("2020-07-12T12:45:00");
+        for (s, expected) in [
+            ("7 o'clock", "2020-07-12T07:00:00"),
+            ("7 o'clock pm", "2020-07-12T19:00:00"),
+            ("7 o'clock in the evening", "2020-07-12T19:00:00"),
+            ("11 at night", "2020-07-12T23:00:00"),
+            // "12 at night" is midnight, not noon
+            ("12 at night", "2020-07-13T00:00:00"),
+            ("tomorrow at 12 at night", "2020-07-13T00:00:00"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                evaluate(parse_time_clue_from_str(s).unwrap(), now).unwrap(),
+                utc(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_prefer_past() {
+        let prefer_past = ParseOptions {
+            prefer_past: true,
+            ..Default::default()
+        };
+        let now = utc("2020-07-08T12:45:00"); // wednesday
+        for (s, expected_default, expected_past) in [
+            ("friday", "2020-07-10T00:00:00", "2020-07-03T00:00:00"),
+            ("friday at 9", "2020-07-10T09:00:00", "2020-07-03T09:00:00"),
+            ("monday", "2020-07-06T00:00:00", "2020-07-06T00:00:00"),
+            (
+                "wednesday at 9",
+                "2020-07-08T09:00:00",
+                "2020-07-08T09:00:00",
+            ),
+            (
+                "wednesday at 19",
+                "2020-07-08T19:00:00",
+                "2020-07-01T19:00:00",
+            ),
+            ("19:43", "2020-07-08T19:43:00", "2020-07-07T19:43:00"),
+            ("8am", "2020-07-08T08:00:00", "2020-07-08T08:00:00"),
+        ]
+        .iter()
+        {
+            let time_clue = || parse_time_clue_from_str(s).unwrap();
+            assert_eq!(
+                evaluate_with_options(time_clue(), now, &ParseOptions::default()).unwrap(),
+                utc(expected_default)
+            );
+            assert_eq!(
+                evaluate_with_options(time_clue(), now, &prefer_past).unwrap(),
+                utc(expected_past)
+            );
+        }
+    }
 }
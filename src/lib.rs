@@ -4,24 +4,42 @@
 //! ## Example
 //!
 //! ```
+//! # #[cfg(feature = "std")] {
 //! use chrono::{Utc, TimeZone};
 //! use htp::parse;
-//! let now = Utc.datetime_from_str("2020-12-24T23:45:00", "%Y-%m-%dT%H:%M:%S").unwrap();
-//! let expected = Utc.datetime_from_str("2020-12-18T19:43:00", "%Y-%m-%dT%H:%M:%S").unwrap();
+//! let now = Utc.with_ymd_and_hms(2020, 12, 24, 23, 45, 0).unwrap();
+//! let expected = Utc.with_ymd_and_hms(2020, 12, 18, 19, 43, 0).unwrap();
 //! let datetime = parse("last friday at 19:43", now).unwrap();
 //! assert_eq!(datetime, expected);
+//! # }
 //! ```
 //!
+//! ## `no_std`
+//!
+//! Disable the default `std` feature to parse time clues in `no_std` environments (requires `alloc`).
+//! Only the `parser`, `binary` and `repr` modules are available then.
+//!
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
 extern crate pest;
 #[macro_use]
 extern crate pest_derive;
 
+#[cfg(feature = "std")]
 use chrono::DateTime;
+#[cfg(feature = "std")]
 use thiserror::Error;
 
+pub mod binary;
+#[cfg(feature = "std")]
 pub mod interpreter;
 pub mod parser;
+pub mod repr;
+
+#[cfg(feature = "std")]
+pub use interpreter::{humanize, BareIntMeaning, ClampTime, ClockHint, ParseOptions};
 
+#[cfg(feature = "std")]
 #[derive(Error, Debug)]
 pub enum HTPError {
     #[error(transparent)]
@@ -33,6 +51,7 @@ pub enum HTPError {
 /// Same as `parse_time_clue(s, now, false)`
 ///
 /// Parse time clue from `s` given reference time `now` in timezone `Tz`.
+#[cfg(feature = "std")]
 pub fn parse<Tz: chrono::TimeZone>(s: &str, now: DateTime<Tz>) -> Result<DateTime<Tz>, HTPError> {
     parse_time_clue(s, now, false)
 }
@@ -41,8 +60,9 @@ pub fn parse<Tz: chrono::TimeZone>(s: &str, now: DateTime<Tz>) -> Result<DateTim
 ///
 /// `assume_next_day`:
 /// * if true: times without a day will be interpreted as times during the following the day.
-/// e.g. 19:43 will be interpreted as tomorrow at 19:43 if current time is > 19:43.
+///   e.g. 19:43 will be interpreted as tomorrow at 19:43 if current time is > 19:43.
 /// * if false: times without a day will be interpreted as times during current day.
+#[cfg(feature = "std")]
 pub fn parse_time_clue<Tz: chrono::TimeZone>(
     s: &str,
     now: DateTime<Tz>,
@@ -52,3 +72,196 @@ pub fn parse_time_clue<Tz: chrono::TimeZone>(
     let datetime = interpreter::evaluate_time_clue(time_clue, now, assume_next_day)?;
     Ok(datetime)
 }
+
+/// Parse time clue from `s` given reference time `now` in timezone `Tz` and evaluation `options`.
+#[cfg(feature = "std")]
+pub fn parse_with_options<Tz: chrono::TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+    options: &ParseOptions,
+) -> Result<DateTime<Tz>, HTPError> {
+    let time_clue = parser::parse_time_clue_from_str(s)?;
+    let datetime = interpreter::evaluate_with_options(time_clue, now, options)?;
+    Ok(datetime)
+}
+
+/// Parse date from `s` given reference time `now` in timezone `Tz` e.g. "tomorrow", "25/12/2020".
+///
+/// Fails if `s` has a time component e.g. "9am", "tomorrow at 9".
+#[cfg(feature = "std")]
+pub fn parse_date<Tz: chrono::TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<chrono::NaiveDate, HTPError> {
+    let time_clue = parser::parse_time_clue_from_str(s)?;
+    let date = interpreter::evaluate_date(time_clue, now, &ParseOptions::default())?;
+    Ok(date)
+}
+
+/// Parse the longest prefix of `s` forming a valid time clue given reference time `now` in timezone `Tz`.
+///
+/// Returns the datetime and the length in bytes of the consumed prefix, prefixes end on word boundaries
+/// e.g. "last friday at 9 and then" consumes "last friday at 9".
+///
+/// Returns `None` if no prefix of `s` is a valid time clue.
+#[cfg(feature = "std")]
+pub fn parse_best_effort<Tz: chrono::TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Option<(DateTime<Tz>, usize)> {
+    let word_ends: Vec<usize> = s
+        .char_indices()
+        .map(|(i, c)| (i + c.len_utf8(), c))
+        .filter(|(end, c)| {
+            !c.is_whitespace() && s[*end..].chars().next().map_or(true, char::is_whitespace)
+        })
+        .map(|(end, _)| end)
+        .collect();
+    word_ends
+        .into_iter()
+        .rev()
+        .find_map(|end| parse(&s[..end], now.clone()).ok().map(|d| (d, end)))
+}
+
+/// Parse recurrence from `s` e.g. "every monday", "every 2 days".
+///
+/// Returns `Ok(None)` if `s` is a valid time clue that does not describe a recurrence.
+#[cfg(feature = "std")]
+pub fn parse_recurrence(s: &str) -> Result<Option<parser::Recurrence>, HTPError> {
+    let recurrence = parser::parse_recurrence_from_str(s)?;
+    Ok(recurrence)
+}
+
+/// Parse time of day from `s`, ignoring any date information e.g. "7pm", "19:43", "noon".
+///
+/// Fails if `s` does not specify a time e.g. "tomorrow".
+#[cfg(feature = "std")]
+pub fn parse_time_only(s: &str) -> Result<(u32, u32, u32, Option<parser::AMPM>), HTPError> {
+    let time = parser::parse_time_only_from_str(s)?;
+    Ok(time)
+}
+
+/// Parse range from `s` given reference time `now` in timezone `Tz`.
+///
+/// Returns the `(start, end)` of the range e.g. "last 7 days", "next 3 weeks", "within 2 days", "2020-12".
+#[cfg(feature = "std")]
+pub fn parse_range<Tz: chrono::TimeZone>(
+    s: &str,
+    now: DateTime<Tz>,
+) -> Result<(DateTime<Tz>, DateTime<Tz>), HTPError> {
+    let range_clue = parser::parse_range_clue_from_str(s)?;
+    let range = interpreter::evaluate_range(range_clue, now)?;
+    Ok(range)
+}
+
+/// Parse duration from `s` e.g. "2 hours", "an hour", "half an hour".
+///
+/// Durations are nominal, not calendar-aware: months are assumed to be 30 days long
+/// and years 365 days long (decades and centuries), unlike `parse` which shifts `now`
+/// using calendar arithmetic.
+///
+/// Fails with `ParseError::DurationOverflow` if the duration is out of range e.g. "999999999999999w".
+#[cfg(feature = "std")]
+pub fn parse_duration(s: &str) -> Result<chrono::Duration, HTPError> {
+    let (n, quantifier) = parser::parse_duration_from_str(s)?;
+    let duration = interpreter::relative_duration(n, &quantifier)
+        .ok_or_else(|| parser::ParseError::DurationOverflow(s.to_string()))?;
+    Ok(duration)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use crate::interpreter::EvaluationError;
+    use crate::parser::ParseError;
+    use crate::{parse, parse_best_effort, parse_date, parse_duration, HTPError};
+    use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S")
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn test_parse_best_effort() {
+        let now = utc("2020-12-24T23:45:00");
+        for (s, prefix) in [
+            ("last friday at 9 and then", "last friday at 9"),
+            ("last friday at 9", "last friday at 9"),
+            ("last friday at", "last friday"),
+            ("tomorrow at 9 UTC please", "tomorrow at 9 UTC"),
+            ("in 2 hours or so", "in 2 hours"),
+            ("  now   ", "  now"),
+            ("2020-12-25 is christmas", "2020-12-25"),
+        ]
+        .iter()
+        {
+            let expected = parse(prefix, now).unwrap();
+            assert_eq!(
+                parse_best_effort(s, now),
+                Some((expected, prefix.len())),
+                "{}",
+                s
+            );
+        }
+        assert_eq!(parse_best_effort("", now), None);
+        assert_eq!(parse_best_effort("see you soon", now), None);
+        assert_eq!(parse_best_effort("2020-02-30 or later", now), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let now = utc("2020-12-24T23:45:00");
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        for (s, expected) in [
+            ("tomorrow", date(2020, 12, 25)),
+            ("yesterday", date(2020, 12, 23)),
+            ("25/12/2020", date(2020, 12, 25)),
+            ("2020-12-25", date(2020, 12, 25)),
+            ("next friday", date(2020, 12, 25)),
+            ("the 3rd", date(2020, 12, 3)),
+            ("tomorrow week", date(2021, 1, 1)),
+            ("2020-12-25 + 3 days", date(2020, 12, 28)),
+            ("end of month", date(2020, 12, 31)),
+        ]
+        .iter()
+        {
+            assert_eq!(parse_date(s, now).unwrap(), *expected, "{}", s);
+        }
+        // the date is the date of `now` in its own timezone
+        let tokyo = now.with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap());
+        assert_eq!(parse_date("tomorrow", tokyo).unwrap(), date(2020, 12, 26));
+        assert_eq!(parse_date("2020-12-25", tokyo).unwrap(), date(2020, 12, 25));
+        for s in [
+            "9am",
+            "now",
+            "tomorrow at 9",
+            "2020-12-25T19:43:00",
+            "in 2 days",
+            "2 hours after end of month",
+        ]
+        .iter()
+        {
+            assert!(
+                matches!(
+                    parse_date(s, now),
+                    Err(HTPError::EvaluationError(EvaluationError::NotADate))
+                ),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2 hours").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::minutes(90));
+        for s in ["999999999999999w", "99999999999 centuries"].iter() {
+            assert!(matches!(
+                parse_duration(s),
+                Err(HTPError::ParseError(ParseError::DurationOverflow(_)))
+            ));
+        }
+    }
+}
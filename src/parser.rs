@@ -1,35 +1,128 @@
-use chrono::Weekday;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use chrono::{FixedOffset, Weekday};
+use core::fmt;
+use core::fmt::Formatter;
 use pest::iterators::{Pair, Pairs};
 use pest::Parser;
-use std::fmt;
-use std::fmt::Formatter;
-use thiserror::Error;
 
 #[derive(Parser)]
 #[grammar = "time.pest"]
 pub struct TimeParser;
 
+/// Minutes from now of "in a moment".
+pub const MOMENT_MINUTES: usize = 5;
+
 pub type YMD = (i32, u32, u32);
 pub type HMS = (u32, u32, u32);
 
-#[derive(Error, Debug)]
+// `Display` and `Error` are implemented by hand since `thiserror` requires `std`.
+#[derive(Debug)]
 pub enum ParseError {
-    #[error("invalid integer")]
-    ParseInt(#[from] std::num::ParseIntError),
-    #[error(transparent)]
-    PestError(#[from] pest::error::Error<Rule>),
-    #[error("unexpected non matching pattern")]
+    ParseInt(core::num::ParseIntError),
+    PestError(pest::error::Error<Rule>),
     UnexpectedNonMatchingPattern,
-    #[error("unknown weekday: `{0}`")]
     UnknownWeekday(String),
-    #[error("unknown shortcut day: `{0}`")]
     UnknownShortcutDay(String),
-    #[error("unknown modifier: `{0}`")]
     UnknownModifier(String),
-    #[error("unknown quantifier `{0}`")]
     UnknownQuantifier(String),
-    #[error("unknown am or pm `{0}`")]
     UnknownAMPM(String),
+    UnknownZone(String),
+    AmbiguousZone(String),
+    MissingTime,
+    UnknownFraction(String),
+    UnsupportedFraction(String),
+    ContradictoryDirection(String),
+    UnknownWeekOfMonth(String),
+    UnknownMonth(String),
+    UnknownEdge(String),
+    UnknownPeriod(String),
+    UnknownDirection(String),
+    UnknownPartOfDay(String),
+    ModifiedShortcutDay(String),
+    OrdinalSuffixMismatch(String),
+    IncompleteClue(String),
+    DurationOverflow(String),
+    UnknownNumber(String),
+    EmptyInput,
+    NoonMidnightMismatch(String),
+    ShiftedTime(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::ParseInt(_) => write!(f, "invalid integer"),
+            ParseError::PestError(e) => fmt::Display::fmt(e, f),
+            ParseError::UnexpectedNonMatchingPattern => {
+                write!(f, "unexpected non matching pattern")
+            }
+            ParseError::UnknownWeekday(s) => write!(f, "unknown weekday: `{}`", s),
+            ParseError::UnknownShortcutDay(s) => write!(f, "unknown shortcut day: `{}`", s),
+            ParseError::UnknownModifier(s) => write!(f, "unknown modifier: `{}`", s),
+            ParseError::UnknownQuantifier(s) => write!(f, "unknown quantifier `{}`", s),
+            ParseError::UnknownAMPM(s) => write!(f, "unknown am or pm `{}`", s),
+            ParseError::UnknownZone(s) => write!(f, "unknown zone `{}`", s),
+            ParseError::AmbiguousZone(s) => {
+                write!(f, "ambiguous zone `{}`, use a numeric offset instead", s)
+            }
+            ParseError::MissingTime => write!(f, "missing time"),
+            ParseError::EmptyInput => write!(f, "empty input"),
+            ParseError::ShiftedTime(s) => {
+                write!(f, "`{}`: time of day of a shifted date is not supported", s)
+            }
+            ParseError::NoonMidnightMismatch(s) => {
+                write!(f, "`{}`: only 12 can be followed by noon or midnight", s)
+            }
+            ParseError::UnknownFraction(s) => write!(f, "unknown fraction `{}`", s),
+            ParseError::UnsupportedFraction(s) => {
+                write!(f, "unsupported fraction `{}` of a minute", s)
+            }
+            ParseError::UnknownWeekOfMonth(s) => write!(f, "unknown week of month `{}`", s),
+            ParseError::UnknownMonth(s) => write!(f, "unknown month `{}`", s),
+            ParseError::UnknownEdge(s) => write!(f, "unknown edge `{}`", s),
+            ParseError::UnknownPeriod(s) => write!(f, "unknown period `{}`", s),
+            ParseError::UnknownDirection(s) => write!(f, "unknown direction `{}`", s),
+            ParseError::UnknownPartOfDay(s) => write!(f, "unknown part of day `{}`", s),
+            ParseError::OrdinalSuffixMismatch(s) => write!(f, "ordinal suffix mismatch `{}`", s),
+            ParseError::ModifiedShortcutDay(s) => {
+                write!(f, "`{}`: shortcut days cannot have a modifier", s)
+            }
+            ParseError::ContradictoryDirection(s) => {
+                write!(f, "contradictory direction `{}`: both past and future", s)
+            }
+            ParseError::DurationOverflow(s) => write!(f, "duration overflow `{}`", s),
+            ParseError::UnknownNumber(s) => write!(f, "unknown number `{}`", s),
+            ParseError::IncompleteClue(s) => {
+                write!(f, "incomplete clue `{}`: missing day or time", s)
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ParseError::ParseInt(e) => Some(e),
+            ParseError::PestError(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<core::num::ParseIntError> for ParseError {
+    fn from(e: core::num::ParseIntError) -> Self {
+        ParseError::ParseInt(e)
+    }
+}
+
+impl From<pest::error::Error<Rule>> for ParseError {
+    fn from(e: pest::error::Error<Rule>) -> Self {
+        ParseError::PestError(e)
+    }
 }
 
 fn weekday_from(s: &str) -> Result<Weekday, ParseError> {
@@ -45,7 +138,7 @@ fn weekday_from(s: &str) -> Result<Weekday, ParseError> {
     }
 }
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AMPM {
     AM,
     PM,
@@ -61,50 +154,228 @@ impl fmt::Display for AMPM {
 }
 
 fn am_or_pm_from(s: &str) -> Result<AMPM, ParseError> {
-    match s {
+    match s.to_lowercase().replace('.', "").as_str() {
         "am" => Ok(AMPM::AM),
         "pm" => Ok(AMPM::PM),
         _ => Err(ParseError::UnknownAMPM(s.to_string())),
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// Offset ±HHMM or ±HH:MM: "+0200", "-05:00"
+fn numeric_offset_from(s: &str) -> Option<FixedOffset> {
+    let (sign, hhmm) = match s.split_at(1) {
+        ("+", hhmm) => (1, hhmm),
+        ("-", hhmm) => (-1, hhmm),
+        _ => return None,
+    };
+    let hhmm = hhmm.replace(':', "");
+    let hours: i32 = hhmm.get(..2)?.parse().ok()?;
+    let minutes: i32 = hhmm.get(2..)?.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Only `UTC`, `GMT` and numeric offsets are zones, IANA names are not supported.
+///
+/// Other abbreviations are rejected with `ParseError::AmbiguousZone`: a fixed offset for `EST`
+/// or `CEST` would be wrong half of the year, and `CST` names several zones.
+fn zone_from(s: &str) -> Result<FixedOffset, ParseError> {
+    if s.starts_with(['+', '-']) {
+        return numeric_offset_from(s).ok_or_else(|| ParseError::UnknownZone(s.to_string()));
+    }
+    match s.to_lowercase().as_str() {
+        "utc" | "gmt" => {
+            FixedOffset::east_opt(0).ok_or_else(|| ParseError::UnknownZone(s.to_string()))
+        }
+        "est" | "edt" | "cst" | "cdt" | "mst" | "mdt" | "pst" | "pdt" | "cet" | "cest" => {
+            Err(ParseError::AmbiguousZone(s.to_string()))
+        }
+        _ => Err(ParseError::UnknownZone(s.to_string())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ShortcutDay {
     Today,
     Yesterday,
     Tomorrow,
 }
 
+impl fmt::Display for ShortcutDay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ShortcutDay::Today => write!(f, "today"),
+            ShortcutDay::Yesterday => write!(f, "yesterday"),
+            ShortcutDay::Tomorrow => write!(f, "tomorrow"),
+        }
+    }
+}
+
 fn shortcut_day_from(s: &str) -> Result<ShortcutDay, ParseError> {
     match s {
-        "today" => Ok(ShortcutDay::Today),
-        "yesterday" => Ok(ShortcutDay::Yesterday),
-        "tomorrow" => Ok(ShortcutDay::Tomorrow),
+        "today" | "tdy" => Ok(ShortcutDay::Today),
+        "yesterday" | "yday" => Ok(ShortcutDay::Yesterday),
+        "tomorrow" | "tmrw" | "tmr" => Ok(ShortcutDay::Tomorrow),
         _ => Err(ParseError::UnknownShortcutDay(s.to_string())),
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Modifier {
     Last,
     Next,
 }
 
 fn modifier_from(s: &str) -> Result<Modifier, ParseError> {
-    match s {
-        "last" => Ok(Modifier::Last),
-        "next" => Ok(Modifier::Next),
+    // "the following", "the previous"
+    let modifier = s.strip_prefix("the").map_or(s, str::trim_start);
+    match modifier {
+        "last" | "previous" => Ok(Modifier::Last),
+        "next" | "following" => Ok(Modifier::Next),
         _ => Err(ParseError::UnknownModifier(s.to_string())),
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// Week of a month: "first", "second"... "last"
+///
+/// The first week of a month is the week (starting on monday) containing the 1st of the month,
+/// it may start in the previous month. Likewise the last week is the week containing the
+/// last day of the month.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeekOfMonth {
+    /// 1 for the first week, 2 for the second week...
+    Nth(u32),
+    Last,
+}
+
+fn week_of_month_from(s: &str) -> Result<WeekOfMonth, ParseError> {
+    match s {
+        "first" => Ok(WeekOfMonth::Nth(1)),
+        "second" => Ok(WeekOfMonth::Nth(2)),
+        "third" => Ok(WeekOfMonth::Nth(3)),
+        "fourth" => Ok(WeekOfMonth::Nth(4)),
+        "fifth" => Ok(WeekOfMonth::Nth(5)),
+        "last" => Ok(WeekOfMonth::Last),
+        _ => Err(ParseError::UnknownWeekOfMonth(s.to_string())),
+    }
+}
+
+/// Month: "december", "this month", "next month"
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonthClue {
+    /// Month of the current year, 1 for january... 12 for december
+    Named(u32),
+    This,
+    Relative(Modifier),
+}
+
+fn month_from(s: &str) -> Result<u32, ParseError> {
+    match s {
+        "january" | "jan" => Ok(1),
+        "february" | "feb" => Ok(2),
+        "march" | "mar" => Ok(3),
+        "april" | "apr" => Ok(4),
+        "may" => Ok(5),
+        "june" | "jun" => Ok(6),
+        "july" | "jul" => Ok(7),
+        "august" | "aug" => Ok(8),
+        "september" | "sept" | "sep" => Ok(9),
+        "october" | "oct" => Ok(10),
+        "november" | "nov" => Ok(11),
+        "december" | "dec" => Ok(12),
+        _ => Err(ParseError::UnknownMonth(s.to_string())),
+    }
+}
+
+/// Default time of a part of day: morning 09:00, afternoon 15:00, evening 19:00
+fn part_of_day_from(s: &str) -> Result<HMS, ParseError> {
+    match s {
+        "morning" => Ok((9, 0, 0)),
+        "afternoon" => Ok((15, 0, 0)),
+        "evening" => Ok((19, 0, 0)),
+        _ => Err(ParseError::UnknownPartOfDay(s.to_string())),
+    }
+}
+
+/// Day number from `nth_day` e.g. "1st", "22nd", "3rd", "4th".
+///
+/// The suffix must agree with the number: "11th", "12th", "13th", "21st", "22nd", "23rd"...
+/// "3th" is rejected with `ParseError::OrdinalSuffixMismatch`.
+fn nth_day_from(s: &str, suffix: &str) -> Result<u32, ParseError> {
+    let n: u32 = s[..s.len() - suffix.len()].parse()?;
+    let expected = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    if suffix == expected {
+        Ok(n)
+    } else {
+        Err(ParseError::OrdinalSuffixMismatch(s.to_string()))
+    }
+}
+
+/// Start or end of a period.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edge {
+    /// "start", "beginning"
+    Start,
+    /// "end"
+    End,
+}
+
+fn edge_from(s: &str) -> Result<Edge, ParseError> {
+    match s {
+        "start" | "beginning" => Ok(Edge::Start),
+        "end" => Ok(Edge::End),
+        _ => Err(ParseError::UnknownEdge(s.to_string())),
+    }
+}
+
+/// Current calendar period, weeks start on monday.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+}
+
+fn period_from(s: &str) -> Result<Period, ParseError> {
+    match s {
+        "week" => Ok(Period::Week),
+        "month" => Ok(Period::Month),
+        "year" => Ok(Period::Year),
+        _ => Err(ParseError::UnknownPeriod(s.to_string())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Direction {
+    Before,
+    After,
+}
+
+fn direction_from(s: &str) -> Result<Direction, ParseError> {
+    match s {
+        "before" => Ok(Direction::Before),
+        "after" => Ok(Direction::After),
+        _ => Err(ParseError::UnknownDirection(s.to_string())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Quantifier {
     Min,
     Hours,
     Days,
     Weeks,
     Months,
+    Decades,
+    Centuries,
 }
 
 fn quantifier_from(s: &str) -> Result<Quantifier, ParseError> {
@@ -114,32 +385,400 @@ fn quantifier_from(s: &str) -> Result<Quantifier, ParseError> {
         "days" | "day" | "d" => Ok(Quantifier::Days),
         "weeks" | "week" | "w" => Ok(Quantifier::Weeks),
         "months" | "month" => Ok(Quantifier::Months),
+        "decades" | "decade" => Ok(Quantifier::Decades),
+        "centuries" | "century" => Ok(Quantifier::Centuries),
         _ => Err(ParseError::UnknownQuantifier(s.to_string())),
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// Coarse classification of `TimeClue`s, see `TimeClue::kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimeClueKind {
+    /// `TimeClue::Now`
+    Now,
+    /// Time of day without date:
+    /// `TimeClue::Time`, `TimeClue::BareInt`, `TimeClue::Noon`, `TimeClue::Midnight`
+    Time,
+    /// Relative to now: `TimeClue::Relative`, `TimeClue::RelativeFuture`
+    Relative,
+    /// Day with an optional time of day:
+    /// `TimeClue::RelativeDayAt`, `TimeClue::SameWeekDayAt`, `TimeClue::ShortcutDayAt`
+    DayAt,
+    /// Absolute date and time: `TimeClue::ISO`, `TimeClue::Ordinal`
+    Absolute,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum TimeClue {
-    /// Now.
+    /// Now: "now", "right now", "just now", "at once"
     Now,
-    /// Time without date: "19:43:42", "18", "8", "7pm", "3am"
+    /// Time without date: "19:43:42", "18:00", "7 o'clock", "7pm", "3am"
     Time(HMS, Option<AMPM>),
+    /// Bare integer: "18", "8"
+    ///
+    /// An hour without date unless `ParseOptions::bare_int` says otherwise.
+    BareInt(u32),
+    /// Midnight without date: "midnight", "midnight tonight", "12 midnight"
+    Midnight,
+    /// Noon without date: "noon", "midday", "12 noon"
+    Noon,
     /// Relative (past) time clue: "4 minutes ago"
     Relative(usize, Quantifier),
     /// last/next `<day>` at `<time>`: "last friday at 12"
+    ///
+    /// Never today: "next friday" is the first friday strictly after today,
+    /// "last friday" is the last friday strictly before today.
+    /// e.g. on friday 2020-07-10, "next friday" is 2020-07-17 and "last friday" is 2020-07-03.
     RelativeDayAt(Modifier, Weekday, Option<HMS>, Option<AMPM>),
     /// Relative (future) time clue: "in 4 minutes"
     RelativeFuture(usize, Quantifier),
     /// Same week day at `<time>`: "monday at 4"
     SameWeekDayAt(Weekday, Option<HMS>, Option<AMPM>),
     /// `<shortcut_day>` at `<time>`: "yesterday at 4", "tomorrow"
+    ///
+    /// Also "this morning" (09:00), "this afternoon" (15:00) and "this evening" (19:00) today.
     ShortcutDayAt(ShortcutDay, Option<HMS>, Option<AMPM>),
     /// YYYY-MM-DDThh:mm:ss or YYYY/MM/DDThh:mm:ss: "2020-12-25T19:43:00"
     ISO(YMD, HMS),
+    /// `<weekday>` `<date>`, the weekday must match the date: "friday 2020-12-25", "fri, 25/12/2020"
+    WeekdayISO(Weekday, YMD, HMS),
+    /// Start of a week of a month: "first week of december", "the last week of next month"
+    WeekOfMonth(WeekOfMonth, MonthClue),
+    /// n-th weekday after/before today: "3 mondays from now", "2 fridays ago"
+    ///
+    /// "1 monday from now" is the same as "next monday", "1 monday ago" is the same as "last monday".
+    NthWeekday(usize, Weekday, Direction),
+    /// Day of the current month at `<time>`: "the 3rd", "the 22nd at 5pm"
+    DayOfMonth(u32, Option<HMS>, Option<AMPM>),
+    /// First or last day of the current period: "end of month", "the start of the week"
+    PeriodEdge(Edge, Period),
+    /// `<duration>` before/after `<time clue>`: "3 days before end of month", "2020-12-25 + 3 days",
+    /// "2 days before 2020-12-25"
+    ///
+    /// "a week from friday" and "a week on friday" are 1 week after "next friday",
+    /// i.e. 7 days after the next friday strictly after today.
+    ///
+    /// "tomorrow week" is 1 week after "tomorrow", "yesterday week" is 1 week before "yesterday".
+    Offset(usize, Quantifier, Direction, Box<TimeClue>),
+    /// Start of an ISO year or month YYYY, YYYY-MM: "2020", "2020-12"
+    PartialISO(i32, Option<u32>),
+    /// ISO ordinal date YYYY-DDD (year and day of year): "2020-360"
+    Ordinal(i32, u32),
+    /// `<time clue>` in a given zone: "tomorrow at 9 UTC", "friday at 17:00 -05:00", "9am +0200"
+    WithOffset(Box<TimeClue>, FixedOffset),
+}
+
+impl TimeClue {
+    /// Coarse classification of this time clue.
+    ///
+    /// Prefer matching on `kind()` over matching on `TimeClue` when
+    /// the exact variant does not matter, `TimeClue` may gain new variants.
+    ///
+    /// `TimeClue::Offset` and `TimeClue::WithOffset` have the kind of the time clue they wrap.
+    pub fn kind(&self) -> TimeClueKind {
+        match self {
+            TimeClue::Now => TimeClueKind::Now,
+            TimeClue::Time(_, _) | TimeClue::BareInt(_) | TimeClue::Noon | TimeClue::Midnight => {
+                TimeClueKind::Time
+            }
+            TimeClue::Relative(_, _) | TimeClue::RelativeFuture(_, _) => TimeClueKind::Relative,
+            TimeClue::RelativeDayAt(_, _, _, _)
+            | TimeClue::SameWeekDayAt(_, _, _)
+            | TimeClue::ShortcutDayAt(_, _, _)
+            | TimeClue::NthWeekday(_, _, _)
+            | TimeClue::DayOfMonth(_, _, _)
+            | TimeClue::WeekOfMonth(_, _)
+            | TimeClue::PeriodEdge(_, _) => TimeClueKind::DayAt,
+            TimeClue::ISO(_, _)
+            | TimeClue::WeekdayISO(_, _, _)
+            | TimeClue::PartialISO(_, _)
+            | TimeClue::Ordinal(_, _) => TimeClueKind::Absolute,
+            TimeClue::Offset(_, _, _, time_clue) | TimeClue::WithOffset(time_clue, _) => {
+                time_clue.kind()
+            }
+        }
+    }
+
+    /// Whether this time clue describes a date without time e.g. "tomorrow", "25/12/2020".
+    ///
+    /// ISO dates at 00:00:00 are date only since "25/12/2020" is parsed as `TimeClue::ISO` at 00:00:00.
+    /// Time clues with a zone are never date only.
+    pub fn is_date_only(&self) -> bool {
+        match self {
+            TimeClue::RelativeDayAt(_, _, hms, _)
+            | TimeClue::SameWeekDayAt(_, hms, _)
+            | TimeClue::ShortcutDayAt(_, hms, _)
+            | TimeClue::DayOfMonth(_, hms, _) => hms.is_none(),
+            TimeClue::ISO(_, hms) | TimeClue::WeekdayISO(_, _, hms) => *hms == (0, 0, 0),
+            TimeClue::NthWeekday(_, _, _)
+            | TimeClue::WeekOfMonth(_, _)
+            | TimeClue::PeriodEdge(_, _)
+            | TimeClue::PartialISO(_, _)
+            | TimeClue::Ordinal(_, _) => true,
+            TimeClue::Offset(_, quantifier, _, time_clue) => {
+                !matches!(quantifier, Quantifier::Min | Quantifier::Hours)
+                    && time_clue.is_date_only()
+            }
+            TimeClue::Now
+            | TimeClue::Time(_, _)
+            | TimeClue::BareInt(_)
+            | TimeClue::Midnight
+            | TimeClue::Noon
+            | TimeClue::Relative(_, _)
+            | TimeClue::RelativeFuture(_, _)
+            | TimeClue::WithOffset(_, _) => false,
+        }
+    }
+}
+
+fn number_word_from(s: &str) -> Result<usize, ParseError> {
+    match s {
+        "one" => Ok(1),
+        "two" => Ok(2),
+        "three" => Ok(3),
+        "four" => Ok(4),
+        "five" => Ok(5),
+        "six" => Ok(6),
+        "seven" => Ok(7),
+        "eight" => Ok(8),
+        "nine" => Ok(9),
+        "ten" => Ok(10),
+        "eleven" => Ok(11),
+        "twelve" => Ok(12),
+        _ => Err(ParseError::UnknownNumber(s.to_string())),
+    }
+}
+
+/// Count from either an integer, a number word or an article: "2", "two", "a", "an"
+fn count_from(rule: Rule, s: &str) -> Result<usize, ParseError> {
+    match rule {
+        Rule::int => Ok(s.parse()?),
+        Rule::number_word => number_word_from(s),
+        Rule::article => Ok(1),
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
+}
+
+/// `whole` and `fraction` of one `quantifier` as a whole number of a smaller quantifier
+/// e.g. "half" an hour is 30 minutes, "two and a half" hours is 150 minutes.
+fn fraction_of(
+    whole: usize,
+    fraction: &str,
+    quantifier: Quantifier,
+) -> Result<(usize, Quantifier), ParseError> {
+    let divisor = match fraction {
+        "half" => 2,
+        "quarter" => 4,
+        _ => return Err(ParseError::UnknownFraction(fraction.to_string())),
+    };
+    let (n, q) = match quantifier {
+        Quantifier::Hours => (60, Quantifier::Min),
+        Quantifier::Days => (24, Quantifier::Hours),
+        Quantifier::Weeks => (7 * 24, Quantifier::Hours),
+        Quantifier::Months => (30 * 24, Quantifier::Hours), // assume 1 month = 30 days
+        Quantifier::Decades => (10 * 12, Quantifier::Months),
+        Quantifier::Centuries => (100 * 12, Quantifier::Months),
+        Quantifier::Min => return Err(ParseError::UnsupportedFraction(fraction.to_string())),
+    };
+    let n = whole
+        .checked_mul(n)
+        .and_then(|whole| whole.checked_add(n / divisor))
+        .ok_or_else(|| ParseError::DurationOverflow(format!("{} and {}", whole, fraction)))?;
+    Ok((n, q))
+}
+
+/// Sum of compact duration `parts` e.g. "1h30m", in the smallest unit of the parts: 90 minutes.
+fn compact_duration_from(
+    s: &str,
+    parts: &[(Rule, &str)],
+) -> Result<(usize, Quantifier), ParseError> {
+    let overflow = || ParseError::DurationOverflow(s.to_string());
+    let mut total_minutes: usize = 0;
+    let mut smallest = (Quantifier::Weeks, 7 * 24 * 60);
+    for part in parts.chunks(2) {
+        let (n, unit) = match part {
+            [(Rule::int, n), (Rule::compact_unit, unit)] => (n.parse::<usize>()?, *unit),
+            _ => return Err(ParseError::UnexpectedNonMatchingPattern),
+        };
+        let (quantifier, minutes) = match unit {
+            "min" | "m" => (Quantifier::Min, 1),
+            "h" => (Quantifier::Hours, 60),
+            "d" => (Quantifier::Days, 24 * 60),
+            "w" => (Quantifier::Weeks, 7 * 24 * 60),
+            _ => return Err(ParseError::UnknownQuantifier(unit.to_string())),
+        };
+        total_minutes = n
+            .checked_mul(minutes)
+            .and_then(|part_minutes| total_minutes.checked_add(part_minutes))
+            .ok_or_else(overflow)?;
+        if minutes < smallest.1 {
+            smallest = (quantifier, minutes);
+        }
+    }
+    let (quantifier, minutes) = smallest;
+    Ok((total_minutes / minutes, quantifier))
+}
+
+fn parse_duration(rules_and_str: &[(Rule, &str)]) -> Result<(usize, Quantifier), ParseError> {
+    match rules_and_str {
+        [(Rule::compact_duration, s), parts @ ..] => compact_duration_from(s, parts),
+        [(Rule::fractional, _), (Rule::fraction, f), (Rule::quantifier, q)] => {
+            let q = quantifier_from(q)?;
+            fraction_of(0, f, q)
+        }
+        [(r, s), (Rule::and_fraction, _), (Rule::fraction, f), (Rule::quantifier, q)] => {
+            let n = count_from(*r, s)?;
+            let q = quantifier_from(q)?;
+            fraction_of(n, f, q)
+        }
+        [(r, s), (Rule::quantifier, q)] => {
+            let n = count_from(*r, s)?;
+            let q = quantifier_from(q)?;
+            Ok((n, q))
+        }
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
+}
+
+/// Range of time.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum RangeClue {
+    /// Range ending now: "last 7 days", "last week"
+    Last(usize, Quantifier),
+    /// Range starting now: "next 3 weeks", "next month", "in the next hour", "within 2 days"
+    Next(usize, Quantifier),
+    /// ISO year or month YYYY, YYYY-MM: "2020", "2020-12"
+    PartialISO(i32, Option<u32>),
+}
+
+/// Year and optional month from `partial_iso` rules.
+fn parse_partial_iso(rules_and_str: &[(Rule, &str)]) -> Result<(i32, Option<u32>), ParseError> {
+    match rules_and_str {
+        [(Rule::year, y)] => Ok((y.parse()?, None)),
+        [(Rule::year, y), (Rule::month, m)] => Ok((y.parse()?, Some(m.parse()?))),
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
+}
+
+fn parse_range_clue(pairs: &[Pair<Rule>]) -> Result<RangeClue, ParseError> {
+    let rules_and_str: Vec<(Rule, &str)> = pairs
+        .iter()
+        .map(|pair| (pair.as_rule(), pair.as_str()))
+        .collect();
+    match rules_and_str.as_slice() {
+        [(Rule::range_clue, _), (Rule::partial_iso, _), partial_iso @ .., (Rule::EOI, _)] => {
+            let (year, month) = parse_partial_iso(partial_iso)?;
+            Ok(RangeClue::PartialISO(year, month))
+        }
+        [(Rule::range_clue, _), (Rule::last_range, _), (Rule::quantifier, q), (Rule::EOI, _)] => {
+            let q = quantifier_from(q)?;
+            Ok(RangeClue::Last(1, q))
+        }
+        [(Rule::range_clue, _), (Rule::last_range, _), (Rule::int, n), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        {
+            let n: usize = n.parse()?;
+            let q = quantifier_from(q)?;
+            Ok(RangeClue::Last(n, q))
+        }
+        [(Rule::range_clue, _), (Rule::next_range, _), (Rule::quantifier, q), (Rule::EOI, _)] => {
+            let q = quantifier_from(q)?;
+            Ok(RangeClue::Next(1, q))
+        }
+        [(Rule::range_clue, _), (Rule::next_range, _), (Rule::int, n), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        {
+            let n: usize = n.parse()?;
+            let q = quantifier_from(q)?;
+            Ok(RangeClue::Next(n, q))
+        }
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
+}
+
+/// What a `Recurrence` repeats on.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecurrenceUnit {
+    /// "every monday"
+    Weekday(Weekday),
+    /// "every day", "every 2 weeks"
+    Quantifier(Quantifier),
+}
+
+/// Recurrence: "every monday", "every 2 days"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    /// Number of `unit`s between two occurrences, 1 unless specified e.g. 2 for "every 2 days".
+    pub interval: usize,
+    pub unit: RecurrenceUnit,
+}
+
+fn parse_recurrence(pairs: &[Pair<Rule>]) -> Result<Recurrence, ParseError> {
+    let rules_and_str: Vec<(Rule, &str)> = pairs
+        .iter()
+        .map(|pair| (pair.as_rule(), pair.as_str()))
+        .collect();
+    match rules_and_str.as_slice() {
+        [(Rule::recurrence_clue, _), (Rule::every_weekday, _), (Rule::weekday, w), (Rule::EOI, _)] =>
+        {
+            let w = weekday_from(w)?;
+            Ok(Recurrence {
+                interval: 1,
+                unit: RecurrenceUnit::Weekday(w),
+            })
+        }
+        [(Rule::recurrence_clue, _), (Rule::every_interval, _), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        {
+            let q = quantifier_from(q)?;
+            Ok(Recurrence {
+                interval: 1,
+                unit: RecurrenceUnit::Quantifier(q),
+            })
+        }
+        [(Rule::recurrence_clue, _), (Rule::every_interval, _), (Rule::int, n), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        {
+            let n: usize = n.parse()?;
+            let q = quantifier_from(q)?;
+            Ok(Recurrence {
+                interval: n,
+                unit: RecurrenceUnit::Quantifier(q),
+            })
+        }
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
 }
 
 fn parse_time_hms(rules_and_str: &[(Rule, &str)]) -> Result<TimeClue, ParseError> {
+    // "in the morning" is the same as "am", "in the afternoon", "in the evening", "at night" as "pm"
+    // except "12 at night" which is midnight i.e. "12 am"
+    if let Some(i) = rules_and_str
+        .iter()
+        .position(|(rule, _)| *rule == Rule::day_half)
+    {
+        let twelve =
+            matches!(rules_and_str.first(), Some((Rule::hms, h)) if h.parse::<u32>() == Ok(12));
+        let half = rules_and_str[i].1;
+        let am_or_pm = if half.ends_with("morning") || (twelve && half.ends_with("night")) {
+            "am"
+        } else {
+            "pm"
+        };
+        let mut time_hms = rules_and_str[..i].to_vec();
+        time_hms.push((Rule::am_or_pm, am_or_pm));
+        return parse_time_hms(time_hms.as_slice());
+    }
     match rules_and_str {
+        [(Rule::noon, _)] => Ok(TimeClue::Time((12, 0, 0), None)),
+        [(Rule::midnight, _)] => Ok(TimeClue::Time((0, 0, 0), None)),
+        // "12 noon", "12 midnight"
+        [(Rule::hms, h), word @ (Rule::noon | Rule::midnight, w)] => {
+            if h.parse::<u32>()? == 12 {
+                parse_time_hms(&[*word])
+            } else {
+                Err(ParseError::NoonMidnightMismatch(format!("{} {}", h, w)))
+            }
+        }
         [(Rule::hms, h)] => {
             let h: u32 = h.parse()?;
             Ok(TimeClue::Time((h, 0, 0), None))
@@ -183,20 +822,68 @@ fn parse_time_clue(pairs: &[Pair<Rule>]) -> Result<TimeClue, ParseError> {
         .map(|pair| (pair.as_rule(), pair.as_str()))
         .collect();
     match rules_and_str.as_slice() {
+        [time_clue @ .., (Rule::zone, z), eoi @ (Rule::EOI, _)] => {
+            let offset = zone_from(z)?;
+            let mut time_clue = time_clue.to_vec();
+            time_clue.push(*eoi);
+            let time_clue = parse_time_clue_rules(time_clue.as_slice())?;
+            Ok(TimeClue::WithOffset(Box::new(time_clue), offset))
+        }
+        [(Rule::time_clue, c), (Rule::time_day, d), t @ (Rule::noon | Rule::midnight, _), (Rule::mday, m), mday @ .., eoi @ (Rule::EOI, _)] =>
+        {
+            // "noon tomorrow" is the same as "tomorrow at noon"
+            let mut day_at = vec![(Rule::time_clue, *c), (Rule::day_at, *d), (Rule::mday, *m)];
+            day_at.extend_from_slice(mday);
+            day_at.extend_from_slice(&[(Rule::time, t.1), *t, *eoi]);
+            parse_time_clue_rules(day_at.as_slice())
+        }
+        rules_and_str => parse_time_clue_rules(rules_and_str),
+    }
+}
+
+fn parse_time_clue_rules(rules_and_str: &[(Rule, &str)]) -> Result<TimeClue, ParseError> {
+    match rules_and_str {
         [(Rule::time_clue, _), (Rule::now, _), (Rule::EOI, _)] => Ok(TimeClue::Now),
+        [(Rule::time_clue, _), (Rule::moment, _), (Rule::EOI, _)] => {
+            Ok(TimeClue::RelativeFuture(MOMENT_MINUTES, Quantifier::Min))
+        }
+        [(Rule::time_clue, _), (Rule::midnight_tonight, _), (Rule::midnight, _), (Rule::EOI, _)] => {
+            Ok(TimeClue::Midnight)
+        }
+        [(Rule::time_clue, _), (Rule::time, t), (Rule::hms, h), (Rule::EOI, _)]
+            if t.trim_end().chars().all(|c| c.is_ascii_digit()) =>
+        {
+            Ok(TimeClue::BareInt(h.parse()?))
+        }
         [(Rule::time_clue, _), (Rule::time, _), time_hms @ .., (Rule::EOI, _)] => {
-            parse_time_hms(time_hms)
+            let time = parse_time_hms(time_hms)?;
+            match time_hms {
+                // "12 midnight" and "12 at night" are the same as "midnight", "12 noon" as "noon"
+                [.., (Rule::midnight, _)] => Ok(TimeClue::Midnight),
+                [(Rule::hms, _), (Rule::day_half, half)]
+                    if half.ends_with("night")
+                        && time == TimeClue::Time((12, 0, 0), Some(AMPM::AM)) =>
+                {
+                    Ok(TimeClue::Midnight)
+                }
+                [.., (Rule::noon, _)] => Ok(TimeClue::Noon),
+                _ => Ok(time),
+            }
+        }
+        [(Rule::time_clue, _), (Rule::contradictory_direction, s), ..] => {
+            Err(ParseError::ContradictoryDirection(s.to_string()))
         }
-        [(Rule::time_clue, _), (Rule::relative, _), (Rule::int, s), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        [(Rule::time_clue, _), (Rule::incomplete_clue, s), ..] => {
+            Err(ParseError::IncompleteClue(s.trim_end().to_string()))
+        }
+        [(Rule::time_clue, _), (Rule::relative, _), (Rule::duration, _), duration @ .., (Rule::EOI, _)] =>
         {
-            let n: usize = s.parse()?;
-            let q = quantifier_from(q)?;
+            let (n, q) = parse_duration(duration)?;
             Ok(TimeClue::Relative(n, q))
         }
-        [(Rule::time_clue, _), (Rule::relative_future, _), (Rule::int, s), (Rule::quantifier, q), (Rule::EOI, _)] =>
+        [(Rule::time_clue, _), (Rule::relative_future, _), (Rule::duration, _), duration @ .., (Rule::EOI, _)] =>
         {
-            let n: usize = s.parse()?;
-            let q = quantifier_from(q)?;
+            let (n, q) = parse_duration(duration)?;
             Ok(TimeClue::RelativeFuture(n, q))
         }
         [(Rule::time_clue, _), (Rule::day_at, _), (Rule::mday, _), mday @ .., (Rule::EOI, _)] => {
@@ -242,7 +929,14 @@ fn parse_time_clue(pairs: &[Pair<Rule>]) -> Result<TimeClue, ParseError> {
                 _ => Err(ParseError::UnexpectedNonMatchingPattern),
             }
         }
-        [(Rule::time_clue, _), (Rule::iso, _), (Rule::year, y), (Rule::month, m), (Rule::day, d), time_hms @ .., (Rule::EOI, _)] => {
+        [(Rule::time_clue, _), (Rule::iso_date | Rule::iso_basic, _), (Rule::year, y), (Rule::month, m), (Rule::day, d), (Rule::EOI, _)] =>
+        {
+            let y: i32 = y.parse()?;
+            let m: u32 = m.parse()?;
+            let d: u32 = d.parse()?;
+            Ok(TimeClue::ISO((y, m, d), (0, 0, 0)))
+        }
+        [(Rule::time_clue, _), (Rule::iso | Rule::iso_basic, _), (Rule::year, y), (Rule::month, m), (Rule::day, d), time_hms @ .., (Rule::EOI, _)] => {
             match parse_time_hms(time_hms)? {
                 TimeClue::Time(hms, _) => {
                     let y: i32 = y.parse()?;
@@ -253,6 +947,134 @@ fn parse_time_clue(pairs: &[Pair<Rule>]) -> Result<TimeClue, ParseError> {
                 _ => Err(ParseError::UnexpectedNonMatchingPattern),
             }
         }
+        [(Rule::time_clue, _), (Rule::partial_iso, _), partial_iso @ .., (Rule::EOI, _)] => {
+            let (year, month) = parse_partial_iso(partial_iso)?;
+            Ok(TimeClue::PartialISO(year, month))
+        }
+        [(Rule::time_clue, _), (Rule::week_of_month, _), (Rule::week_ordinal, w), month @ .., (Rule::EOI, _)] =>
+        {
+            let week = week_of_month_from(w)?;
+            let month = match month {
+                [(Rule::month_name, m)] => MonthClue::Named(month_from(m)?),
+                [(Rule::this_month, _)] => MonthClue::This,
+                [(Rule::relative_month, _), (Rule::modifier, m)] => {
+                    MonthClue::Relative(modifier_from(m)?)
+                }
+                _ => return Err(ParseError::UnexpectedNonMatchingPattern),
+            };
+            Ok(TimeClue::WeekOfMonth(week, month))
+        }
+        [(Rule::time_clue, _), (Rule::period_edge, _), (Rule::edge, e), (Rule::period, p), (Rule::EOI, _)] => {
+            Ok(TimeClue::PeriodEdge(edge_from(e)?, period_from(p)?))
+        }
+        [c @ (Rule::time_clue, _), (Rule::offset, _), (Rule::duration, _), rules @ ..] => {
+            let at = rules
+                .iter()
+                .position(|(rule, _)| *rule == Rule::direction)
+                .ok_or(ParseError::UnexpectedNonMatchingPattern)?;
+            let (n, q) = parse_duration(&rules[..at])?;
+            let direction = direction_from(rules[at].1)?;
+            let mut anchor = vec![*c];
+            anchor.extend_from_slice(&rules[at + 1..]);
+            let anchor = parse_time_clue_rules(anchor.as_slice())?;
+            Ok(TimeClue::Offset(n, q, direction, Box::new(anchor)))
+        }
+        [c @ (Rule::time_clue, _), (Rule::date_offset, _), rules @ .., eoi @ (Rule::EOI, _)] => {
+            let at = rules
+                .iter()
+                .position(|(rule, _)| *rule == Rule::sign)
+                .ok_or(ParseError::UnexpectedNonMatchingPattern)?;
+            let mut date = vec![*c];
+            date.extend_from_slice(&rules[..at]);
+            date.push(*eoi);
+            let date = parse_time_clue_rules(date.as_slice())?;
+            let direction = match rules[at].1 {
+                "+" => Direction::After,
+                _ => Direction::Before,
+            };
+            match &rules[at + 1..] {
+                [(Rule::duration, _), duration @ ..] => {
+                    let (n, q) = parse_duration(duration)?;
+                    Ok(TimeClue::Offset(n, q, direction, Box::new(date)))
+                }
+                _ => Err(ParseError::UnexpectedNonMatchingPattern),
+            }
+        }
+        [(Rule::time_clue, _), (Rule::nth_weekday, _), (Rule::int, n), (Rule::weekday, w), (direction, _), (Rule::EOI, _)] =>
+        {
+            let direction = match direction {
+                Rule::later => Direction::After,
+                _ => Direction::Before,
+            };
+            Ok(TimeClue::NthWeekday(
+                n.parse()?,
+                weekday_from(w)?,
+                direction,
+            ))
+        }
+        [(Rule::time_clue, _), (Rule::this_part_of_day, _), (Rule::part_of_day, p), (Rule::EOI, _)] =>
+        {
+            let hms = part_of_day_from(p)?;
+            Ok(TimeClue::ShortcutDayAt(ShortcutDay::Today, Some(hms), None))
+        }
+        [(Rule::time_clue, _), (Rule::modified_shortcut_day, s), ..] => {
+            Err(ParseError::ModifiedShortcutDay(s.to_string()))
+        }
+        [c @ (Rule::time_clue, _), (Rule::shortcut_day_week, s), d @ (Rule::shortcut_day, _), time @ ..] =>
+        {
+            let direction = match shortcut_day_from(d.1)? {
+                ShortcutDay::Yesterday => Direction::Before,
+                _ => Direction::After,
+            };
+            let mut day_at = vec![*c, (Rule::day_at, *s), (Rule::mday, d.1), *d];
+            day_at.extend_from_slice(time);
+            let day_at = parse_time_clue_rules(day_at.as_slice())?;
+            Ok(TimeClue::Offset(
+                1,
+                Quantifier::Weeks,
+                direction,
+                Box::new(day_at),
+            ))
+        }
+        [(Rule::time_clue, _), (Rule::day_of_month, _), (Rule::nth_day, n), (Rule::ordinal_suffix, suffix), time @ .., (Rule::EOI, _)] =>
+        {
+            let day = nth_day_from(n, suffix)?;
+            match time {
+                [] => Ok(TimeClue::DayOfMonth(day, None, None)),
+                [(Rule::time, _), time_hms @ ..] => match parse_time_hms(time_hms)? {
+                    TimeClue::Time(hms, am_or_pm) => {
+                        Ok(TimeClue::DayOfMonth(day, Some(hms), am_or_pm))
+                    }
+                    _ => Err(ParseError::UnexpectedNonMatchingPattern),
+                },
+                _ => Err(ParseError::UnexpectedNonMatchingPattern),
+            }
+        }
+        [(Rule::time_clue, _), (Rule::week_from, _), (Rule::weekday, w), (Rule::EOI, _)] => {
+            let next_weekday =
+                TimeClue::RelativeDayAt(Modifier::Next, weekday_from(w)?, None, None);
+            Ok(TimeClue::Offset(
+                1,
+                Quantifier::Weeks,
+                Direction::After,
+                Box::new(next_weekday),
+            ))
+        }
+        [c @ (Rule::time_clue, _), (Rule::weekday_date, _), (Rule::weekday, w), date @ ..] => {
+            let weekday = weekday_from(w)?;
+            let mut date_clue = vec![*c];
+            date_clue.extend_from_slice(date);
+            match parse_time_clue_rules(date_clue.as_slice())? {
+                TimeClue::ISO(ymd, hms) => Ok(TimeClue::WeekdayISO(weekday, ymd, hms)),
+                _ => Err(ParseError::UnexpectedNonMatchingPattern),
+            }
+        }
+        [(Rule::time_clue, _), (Rule::ordinal, _), (Rule::year, y), (Rule::day_of_year, d), (Rule::EOI, _)] =>
+        {
+            let y: i32 = y.parse()?;
+            let d: u32 = d.parse()?;
+            Ok(TimeClue::Ordinal(y, d))
+        }
         [(Rule::time_clue, _), (Rule::date, _), (Rule::day, d), (Rule::month, m), (Rule::year, y), (Rule::EOI, _)] =>
         {
             let y: i32 = y.parse()?;
@@ -264,37 +1086,170 @@ fn parse_time_clue(pairs: &[Pair<Rule>]) -> Result<TimeClue, ParseError> {
     }
 }
 
+/// Fails with `ParseError::EmptyInput` if `s` is empty or only whitespace.
+fn check_not_empty(s: &str) -> Result<(), ParseError> {
+    if s.trim().is_empty() {
+        Err(ParseError::EmptyInput)
+    } else {
+        Ok(())
+    }
+}
+
 /// Parse time clue from `s`. Prefer `htp::parse`.
 ///
 /// This function is provided in case you wish to interpret time clues
 /// yourself. Prefer `htp::parse`.
+///
+/// Fails with `ParseError::EmptyInput` if `s` is empty or only whitespace.
 pub fn parse_time_clue_from_str(s: &str) -> Result<TimeClue, ParseError> {
+    check_not_empty(s)?;
     let pairs: Pairs<Rule> = TimeParser::parse(Rule::time_clue, s)?;
-    let pairs: Vec<Pair<Rule>> = pairs.flatten().collect();
+    // leading prepositions e.g. "on friday", "by tomorrow" carry no meaning
+    let pairs: Vec<Pair<Rule>> = pairs
+        .flatten()
+        .filter(|pair| pair.as_rule() != Rule::preposition)
+        .collect();
     parse_time_clue(pairs.as_slice())
 }
 
-#[cfg(test)]
-mod test {
-    use crate::parser::{
-        parse_time_clue_from_str, Modifier, Quantifier, ShortcutDay, TimeClue, AMPM,
-    };
-    use chrono::Weekday;
+/// Check that `s` is a valid time clue without evaluating it.
+///
+/// Invalid dates or times e.g. "25:00" or "2020-02-30" are only detected on evaluation.
+pub fn validate(s: &str) -> Result<(), ParseError> {
+    parse_time_clue_from_str(s).map(|_| ())
+}
 
-    #[test]
-    fn test_parse_time_ok() {
-        assert_eq!(
-            TimeClue::Time((9, 0, 0), None),
-            parse_time_clue_from_str("9").unwrap()
-        );
-        assert_eq!(
-            TimeClue::Time((9, 0, 0), Some(AMPM::AM)),
-            parse_time_clue_from_str("9 am").unwrap()
-        );
-        assert_eq!(
-            TimeClue::Time((9, 0, 0), Some(AMPM::PM)),
-            parse_time_clue_from_str("9 pm").unwrap()
-        );
+/// Whether `s` is a valid time clue, see `validate`.
+pub fn is_valid_clue(s: &str) -> bool {
+    validate(s).is_ok()
+}
+
+/// Same as `parse_time_clue_from_str(s)` but also accepts custom shortcut day `aliases`.
+///
+/// The following abbreviations are always accepted:
+/// * today: "tdy"
+/// * yesterday: "yday"
+/// * tomorrow: "tmr", "tmrw"
+///
+/// Use `aliases` for other abbreviations e.g. `[("yd", ShortcutDay::Yesterday)]`.
+/// Aliases must be whole words in `s`.
+pub fn parse_time_clue_from_str_with_aliases(
+    s: &str,
+    aliases: &[(&str, ShortcutDay)],
+) -> Result<TimeClue, ParseError> {
+    let words: Vec<String> = s
+        .split_whitespace()
+        .map(
+            |word| match aliases.iter().find(|(alias, _)| *alias == word) {
+                Some((_, shortcut_day)) => shortcut_day.to_string(),
+                None => word.to_string(),
+            },
+        )
+        .collect();
+    parse_time_clue_from_str(&words.join(" "))
+}
+
+/// Parse time of day from `s`, ignoring any date information.
+///
+/// e.g. "7pm", "19:43", "noon", "tomorrow at 9"
+///
+/// Fails with `ParseError::MissingTime` if `s` does not specify a time e.g. "tomorrow",
+/// with `ParseError::ShiftedTime` if the time is shifted e.g. "3 hours after 2020-12-25T10:00".
+pub fn parse_time_only_from_str(s: &str) -> Result<(u32, u32, u32, Option<AMPM>), ParseError> {
+    check_not_empty(s)?;
+    let pairs: Pairs<Rule> = TimeParser::parse(Rule::time_clue, s)?;
+    for pair in pairs.flatten() {
+        let time_hms: Vec<(Rule, &str)> = match pair.as_rule() {
+            Rule::midnight_tonight => return Ok((0, 0, 0, None)),
+            Rule::offset | Rule::date_offset => {
+                return Err(ParseError::ShiftedTime(s.trim().to_string()))
+            }
+            Rule::part_of_day => {
+                let (h, m, s) = part_of_day_from(pair.as_str())?;
+                return Ok((h, m, s, None));
+            }
+            Rule::time | Rule::iso | Rule::time_day => pair
+                .into_inner()
+                .flatten()
+                .filter(|pair| {
+                    matches!(
+                        pair.as_rule(),
+                        Rule::hms | Rule::am_or_pm | Rule::day_half | Rule::noon | Rule::midnight
+                    )
+                })
+                .map(|pair| (pair.as_rule(), pair.as_str()))
+                .collect(),
+            _ => continue,
+        };
+        return match parse_time_hms(time_hms.as_slice())? {
+            TimeClue::Time((h, m, s), am_or_pm) => Ok((h, m, s, am_or_pm)),
+            _ => Err(ParseError::UnexpectedNonMatchingPattern),
+        };
+    }
+    Err(ParseError::MissingTime)
+}
+
+/// Parse range clue from `s`. Prefer `htp::parse_range`.
+pub fn parse_range_clue_from_str(s: &str) -> Result<RangeClue, ParseError> {
+    let pairs: Pairs<Rule> = TimeParser::parse(Rule::range_clue, s)?;
+    let pairs: Vec<Pair<Rule>> = pairs.flatten().collect();
+    parse_range_clue(pairs.as_slice())
+}
+
+/// Parse duration from `s` as a number of quantifiers
+/// e.g. "2 hours", "an hour", "half an hour" (30 minutes).
+pub fn parse_duration_from_str(s: &str) -> Result<(usize, Quantifier), ParseError> {
+    let pairs: Pairs<Rule> = TimeParser::parse(Rule::duration_clue, s)?;
+    let rules_and_str: Vec<(Rule, &str)> = pairs
+        .flatten()
+        .map(|pair| (pair.as_rule(), pair.as_str()))
+        .collect();
+    match rules_and_str.as_slice() {
+        [(Rule::duration_clue, _), (Rule::duration, _), duration @ .., (Rule::EOI, _)] => {
+            parse_duration(duration)
+        }
+        _ => Err(ParseError::UnexpectedNonMatchingPattern),
+    }
+}
+
+/// Parse recurrence from `s`.
+///
+/// Returns:
+/// * `Ok(Some(recurrence))` if `s` describes a recurrence e.g. "every monday".
+/// * `Ok(None)` if `s` is a valid time clue that does not describe a recurrence e.g. "monday".
+/// * `Err(_)` otherwise.
+pub fn parse_recurrence_from_str(s: &str) -> Result<Option<Recurrence>, ParseError> {
+    match TimeParser::parse(Rule::recurrence_clue, s) {
+        Ok(pairs) => {
+            let pairs: Vec<Pair<Rule>> = pairs.flatten().collect();
+            parse_recurrence(pairs.as_slice()).map(Some)
+        }
+        Err(_) => parse_time_clue_from_str(s).map(|_| None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::{
+        is_valid_clue, parse_duration_from_str, parse_range_clue_from_str,
+        parse_recurrence_from_str, parse_time_clue_from_str, parse_time_clue_from_str_with_aliases,
+        parse_time_only_from_str, validate, Direction, Edge, Modifier, MonthClue, ParseError,
+        Period, Quantifier, RangeClue, Recurrence, RecurrenceUnit, ShortcutDay, TimeClue,
+        TimeClueKind, WeekOfMonth, AMPM, MOMENT_MINUTES,
+    };
+    use chrono::{FixedOffset, Weekday};
+
+    #[test]
+    fn test_parse_time_ok() {
+        assert_eq!(TimeClue::BareInt(9), parse_time_clue_from_str("9").unwrap());
+        assert_eq!(
+            TimeClue::Time((9, 0, 0), Some(AMPM::AM)),
+            parse_time_clue_from_str("9 am").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Time((9, 0, 0), Some(AMPM::PM)),
+            parse_time_clue_from_str("9 pm").unwrap()
+        );
         assert_eq!(
             TimeClue::Time((9, 30, 0), None),
             parse_time_clue_from_str("9:30").unwrap()
@@ -303,23 +1258,61 @@ mod test {
             TimeClue::Time((9, 30, 56), None),
             parse_time_clue_from_str("9:30:56").unwrap()
         );
+        for s in [
+            "7:30:15 pm",
+            "7:30:15pm",
+            "7:30:15 PM",
+            "7:30:15 p.m.",
+            "7 : 30 : 15 pm",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                TimeClue::Time((7, 30, 15), Some(AMPM::PM)),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        for s in ["7 am", "7AM", "7 a.m.", "7 A.M."].iter() {
+            assert_eq!(
+                TimeClue::Time((7, 0, 0), Some(AMPM::AM)),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_noon_midnight_ok() {
+        for s in ["noon", "midday"].iter() {
+            assert_eq!(TimeClue::Noon, parse_time_clue_from_str(s).unwrap());
+        }
+        for s in ["midnight", "midnight tonight"].iter() {
+            assert_eq!(TimeClue::Midnight, parse_time_clue_from_str(s).unwrap());
+        }
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, Some((12, 0, 0)), None),
+            parse_time_clue_from_str("tomorrow at midday").unwrap()
+        );
+        assert_eq!(
+            TimeClue::SameWeekDayAt(Weekday::Fri, Some((0, 0, 0)), None),
+            parse_time_clue_from_str("friday at midnight").unwrap()
+        );
     }
 
     #[test]
     fn test_parse_relative_ok() {
-        for s in vec!["2 min ago", "2min ago", "2minago", "2   min  ago"].iter() {
+        for s in ["2 min ago", "2min ago", "2minago", "2   min  ago"].iter() {
             assert_eq!(
                 TimeClue::Relative(2, Quantifier::Min),
                 parse_time_clue_from_str(s).unwrap()
             );
         }
-        for s in vec!["2 h ago", "2 hour ago", "2 hours ago"].iter() {
+        for s in ["2 h ago", "2 hour ago", "2 hours ago"].iter() {
             assert_eq!(
                 TimeClue::Relative(2, Quantifier::Hours),
                 parse_time_clue_from_str(s).unwrap()
             );
         }
-        for s in vec!["2 d ago", "2 day ago", "2 days ago"].iter() {
+        for s in ["2 d ago", "2 day ago", "2 days ago"].iter() {
             assert_eq!(
                 TimeClue::Relative(2, Quantifier::Days),
                 parse_time_clue_from_str(s).unwrap()
@@ -329,19 +1322,19 @@ mod test {
 
     #[test]
     fn test_parse_relative_future_ok() {
-        for s in vec!["in 2 min", "in 2min", "in2min", "in  2   min"].iter() {
+        for s in ["in 2 min", "in 2min", "in2min", "in  2   min"].iter() {
             assert_eq!(
                 TimeClue::RelativeFuture(2, Quantifier::Min),
                 parse_time_clue_from_str(s).unwrap()
             );
         }
-        for s in vec!["in 2 h", "in 2 hour", "in 2 hours"].iter() {
+        for s in ["in 2 h", "in 2 hour", "in 2 hours"].iter() {
             assert_eq!(
                 TimeClue::RelativeFuture(2, Quantifier::Hours),
                 parse_time_clue_from_str(s).unwrap()
             );
         }
-        for s in vec!["in 2 d", "in 2 day", "in 2 days"].iter() {
+        for s in ["in 2 d", "in 2 day", "in 2 days"].iter() {
             assert_eq!(
                 TimeClue::RelativeFuture(2, Quantifier::Days),
                 parse_time_clue_from_str(s).unwrap()
@@ -349,6 +1342,59 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_parse_decades_centuries_ok() {
+        assert_eq!(
+            TimeClue::Relative(2, Quantifier::Decades),
+            parse_time_clue_from_str("2 decades ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Relative(1, Quantifier::Centuries),
+            parse_time_clue_from_str("a century ago").unwrap()
+        );
+        assert_eq!(
+            (60, Quantifier::Months),
+            parse_duration_from_str("half a decade").unwrap()
+        );
+        assert_eq!(
+            (2, Quantifier::Days),
+            parse_duration_from_str("2 days").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_compact_duration_ok() {
+        assert_eq!(
+            TimeClue::Relative(90, Quantifier::Min),
+            parse_time_clue_from_str("1h30m ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::RelativeFuture(9, Quantifier::Days),
+            parse_time_clue_from_str("in 1w2d").unwrap()
+        );
+        assert_eq!(
+            (52, Quantifier::Hours),
+            parse_duration_from_str("2d4h").unwrap()
+        );
+        assert_eq!(
+            (30, Quantifier::Min),
+            parse_duration_from_str("30m").unwrap()
+        );
+        assert_eq!(
+            (3, Quantifier::Weeks),
+            parse_duration_from_str("1w2w").unwrap()
+        );
+        assert!(matches!(
+            parse_duration_from_str("99999999999999999999w1m"),
+            Err(ParseError::ParseInt(_))
+        ));
+        assert!(matches!(
+            parse_duration_from_str("9999999999999999999w1m"),
+            Err(ParseError::DurationOverflow(_))
+        ));
+        assert!(parse_duration_from_str("1h 30m").is_err());
+    }
+
     #[test]
     fn test_parse_shortcut_day_ok() {
         assert_eq!(
@@ -394,9 +1440,37 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_parse_following_previous_ok() {
+        for (formal, modifier) in [
+            ("the following friday", "next friday"),
+            ("following friday", "next friday"),
+            ("the   following friday at 9", "next friday at 9"),
+            ("the previous monday", "last monday"),
+            ("previous monday at 7pm", "last monday at 7pm"),
+            (
+                "second week of the following month",
+                "second week of next month",
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                parse_time_clue_from_str(modifier).unwrap(),
+                parse_time_clue_from_str(formal).unwrap(),
+                "{}",
+                formal
+            );
+        }
+        assert!(matches!(
+            parse_time_clue_from_str("the following"),
+            Err(ParseError::IncompleteClue(_))
+        ));
+    }
+
     #[test]
     fn test_parse_same_week_ok() {
-        let weekdays = vec![
+        let weekdays = [
             (Weekday::Mon, "monday"),
             (Weekday::Tue, "tuesday"),
             (Weekday::Wed, "wednesday"),
@@ -407,11 +1481,11 @@ mod test {
         ];
         for (weekday, weekday_str) in weekdays.iter() {
             assert_eq!(
-                TimeClue::SameWeekDayAt(weekday.clone(), None, None),
+                TimeClue::SameWeekDayAt(*weekday, None, None),
                 parse_time_clue_from_str(weekday_str).unwrap()
             )
         }
-        let weekdays = vec![
+        let weekdays = [
             (Weekday::Mon, "mon"),
             (Weekday::Tue, "tue"),
             (Weekday::Wed, "wed"),
@@ -422,7 +1496,7 @@ mod test {
         ];
         for (weekday, weekday_str) in weekdays.iter() {
             assert_eq!(
-                TimeClue::SameWeekDayAt(weekday.clone(), None, None),
+                TimeClue::SameWeekDayAt(*weekday, None, None),
                 parse_time_clue_from_str(weekday_str).unwrap()
             )
         }
@@ -434,7 +1508,27 @@ mod test {
 
     #[test]
     fn test_parse_now_ok() {
-        assert_eq!(TimeClue::Now, parse_time_clue_from_str("now").unwrap());
+        for s in [
+            "now",
+            "right now",
+            "just now",
+            "right   now",
+            "at once",
+            "by now",
+        ]
+        .iter()
+        {
+            assert_eq!(TimeClue::Now, parse_time_clue_from_str(s).unwrap(), "{}", s);
+        }
+        for s in ["in a moment", "in   a moment"].iter() {
+            assert_eq!(
+                TimeClue::RelativeFuture(MOMENT_MINUTES, Quantifier::Min),
+                parse_time_clue_from_str(s).unwrap(),
+                "{}",
+                s
+            );
+        }
+        assert!(parse_time_clue_from_str("rightnow").is_err());
     }
 
     #[test]
@@ -453,5 +1547,1004 @@ mod test {
             TimeClue::ISO((2020, 12, 25), (0, 0, 0)),
             parse_time_clue_from_str("25-12-2020").unwrap()
         );
+
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("25.12.2020").unwrap()
+        );
+
+        assert_eq!(
+            TimeClue::Ordinal(2020, 360),
+            parse_time_clue_from_str("2020-360").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_recurrence_ok() {
+        assert_eq!(
+            Some(Recurrence {
+                interval: 1,
+                unit: RecurrenceUnit::Weekday(Weekday::Mon)
+            }),
+            parse_recurrence_from_str("every monday").unwrap()
+        );
+        assert_eq!(
+            Some(Recurrence {
+                interval: 2,
+                unit: RecurrenceUnit::Quantifier(Quantifier::Days)
+            }),
+            parse_recurrence_from_str("every 2 days").unwrap()
+        );
+        assert_eq!(
+            Some(Recurrence {
+                interval: 1,
+                unit: RecurrenceUnit::Quantifier(Quantifier::Months)
+            }),
+            parse_recurrence_from_str("every month").unwrap()
+        );
+        assert_eq!(None, parse_recurrence_from_str("last monday").unwrap());
+        assert!(parse_recurrence_from_str("every").is_err());
+    }
+
+    #[test]
+    fn test_parse_day_without_at_ok() {
+        for (with_at, without_at) in [
+            ("tomorrow at 9", "tomorrow 9"),
+            ("friday at 19:43", "friday 19:43"),
+            ("next monday at 8am", "next monday 8am"),
+            ("today at noon", "today noon"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                parse_time_clue_from_str(with_at).unwrap(),
+                parse_time_clue_from_str(without_at).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::RelativeDayAt(
+                Modifier::Next,
+                Weekday::Mon,
+                Some((8, 0, 0)),
+                Some(AMPM::AM)
+            ),
+            parse_time_clue_from_str("next monday 8am").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_zone_ok() {
+        assert_eq!(
+            TimeClue::WithOffset(
+                Box::new(TimeClue::ShortcutDayAt(
+                    ShortcutDay::Tomorrow,
+                    Some((9, 0, 0)),
+                    None
+                )),
+                FixedOffset::east_opt(0).unwrap()
+            ),
+            parse_time_clue_from_str("tomorrow at 9 UTC").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WithOffset(
+                Box::new(TimeClue::Time((7, 0, 0), Some(AMPM::PM))),
+                FixedOffset::east_opt(0).unwrap()
+            ),
+            parse_time_clue_from_str("7pm gmt").unwrap()
+        );
+        assert!(parse_time_clue_from_str("2 min ago UTC").is_err());
+        // offsets of these abbreviations depend on daylight saving time or are ambiguous
+        for s in [
+            "friday at 17:00 est",
+            "friday at 17:00 CST",
+            "7pm CEST",
+            "9 PST",
+        ]
+        .iter()
+        {
+            assert!(
+                matches!(
+                    parse_time_clue_from_str(s),
+                    Err(ParseError::AmbiguousZone(_))
+                ),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_numeric_offset_ok() {
+        assert_eq!(
+            TimeClue::WithOffset(
+                Box::new(TimeClue::ShortcutDayAt(
+                    ShortcutDay::Tomorrow,
+                    Some((9, 0, 0)),
+                    Some(AMPM::AM)
+                )),
+                FixedOffset::east_opt(2 * 3600).unwrap()
+            ),
+            parse_time_clue_from_str("tomorrow at 9am +0200").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WithOffset(
+                Box::new(TimeClue::SameWeekDayAt(
+                    Weekday::Fri,
+                    Some((17, 0, 0)),
+                    None
+                )),
+                FixedOffset::west_opt(5 * 3600).unwrap()
+            ),
+            parse_time_clue_from_str("friday 17:00 -0500").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WithOffset(
+                Box::new(TimeClue::Time((19, 43, 0), None)),
+                FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+            ),
+            parse_time_clue_from_str("19:43 +05:30").unwrap()
+        );
+        assert!(matches!(
+            parse_time_clue_from_str("9am +2400"),
+            Err(ParseError::UnknownZone(_))
+        ));
+        assert!(matches!(
+            parse_time_clue_from_str("9am +0260"),
+            Err(ParseError::UnknownZone(_))
+        ));
+        assert!(parse_time_clue_from_str("9am +02").is_err());
+        assert!(parse_time_clue_from_str("9am + 0200").is_err());
+    }
+
+    #[test]
+    fn test_parse_time_only_ok() {
+        assert_eq!(
+            (7, 0, 0, Some(AMPM::PM)),
+            parse_time_only_from_str("7pm").unwrap()
+        );
+        assert_eq!(
+            (19, 43, 0, None),
+            parse_time_only_from_str("19:43").unwrap()
+        );
+        assert_eq!((12, 0, 0, None), parse_time_only_from_str("noon").unwrap());
+        assert_eq!(
+            (0, 0, 0, None),
+            parse_time_only_from_str("midnight").unwrap()
+        );
+        assert_eq!(
+            (9, 30, 0, Some(AMPM::AM)),
+            parse_time_only_from_str("last friday at 9:30 am").unwrap()
+        );
+        assert_eq!(
+            (19, 43, 42, None),
+            parse_time_only_from_str("2020-12-25T19:43:42").unwrap()
+        );
+        assert_eq!(
+            (15, 0, 0, None),
+            parse_time_only_from_str("this afternoon").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_time_only_err() {
+        for s in ["tomorrow", "last friday", "25/12/2020", "2 min ago"].iter() {
+            assert!(matches!(
+                parse_time_only_from_str(s),
+                Err(ParseError::MissingTime)
+            ));
+        }
+        for s in ["", "   "].iter() {
+            assert!(matches!(
+                parse_time_only_from_str(s),
+                Err(ParseError::EmptyInput)
+            ));
+        }
+        for s in [
+            "3 hours after 2020-12-25T10:00",
+            "2020-12-25T10:00 + 3 hours",
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                parse_time_only_from_str(s),
+                Err(ParseError::ShiftedTime(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_unicode_spaces_ok() {
+        for s in [
+            "last\u{a0}friday",
+            "last\tfriday",
+            "\tlast friday",
+            "last friday\u{a0}",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                TimeClue::RelativeDayAt(Modifier::Last, Weekday::Fri, None, None),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        for s in ["2\u{a0}min ago", "2\tmin\tago", "\u{a0}2 min ago"].iter() {
+            assert_eq!(
+                TimeClue::Relative(2, Quantifier::Min),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_shortcut_day_abbreviations_ok() {
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Today, None, None),
+            parse_time_clue_from_str("tdy").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Yesterday, Some((9, 0, 0)), None),
+            parse_time_clue_from_str("yday at 9").unwrap()
+        );
+        for s in ["tmr", "tmrw"].iter() {
+            assert_eq!(
+                TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, None, None),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        assert!(parse_time_clue_from_str("yd").is_err());
+        let aliases = [("yd", ShortcutDay::Yesterday)];
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Yesterday, Some((9, 0, 0)), None),
+            parse_time_clue_from_str_with_aliases("yd at 9", &aliases).unwrap()
+        );
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, None, None),
+            parse_time_clue_from_str_with_aliases("tmr", &aliases).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_time_clue_kind() {
+        let kinds = [
+            ("now", TimeClueKind::Now),
+            ("7pm", TimeClueKind::Time),
+            ("midnight", TimeClueKind::Time),
+            ("2 min ago", TimeClueKind::Relative),
+            ("in 2 days", TimeClueKind::Relative),
+            ("last friday", TimeClueKind::DayAt),
+            ("monday at 9", TimeClueKind::DayAt),
+            ("tomorrow", TimeClueKind::DayAt),
+            ("2020-12-25T19:43:42", TimeClueKind::Absolute),
+            ("25/12/2020", TimeClueKind::Absolute),
+            ("tomorrow at 9 UTC", TimeClueKind::DayAt),
+        ];
+        for (s, kind) in kinds.iter() {
+            assert_eq!(*kind, parse_time_clue_from_str(s).unwrap().kind());
+        }
+    }
+
+    #[test]
+    fn test_parse_relative_future_time_suffix_ok() {
+        for s in [
+            "in 2 days",
+            "in 2 days' time",
+            "in 2 days time",
+            "in 2 days’ time",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                TimeClue::RelativeFuture(2, Quantifier::Days),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        for s in [
+            "in 1 hour",
+            "in an hour",
+            "in an hour's time",
+            "in an hour’s time",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                TimeClue::RelativeFuture(1, Quantifier::Hours),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::Relative(1, Quantifier::Weeks),
+            parse_time_clue_from_str("a week ago").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_time_before_day_ok() {
+        for (time_day, day_at) in [
+            ("noon tomorrow", "tomorrow at noon"),
+            ("midnight friday", "friday at midnight"),
+            ("midday next monday", "next monday at midday"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                parse_time_clue_from_str(day_at).unwrap(),
+                parse_time_clue_from_str(time_day).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, Some((12, 0, 0)), None),
+            parse_time_clue_from_str("noon tomorrow").unwrap()
+        );
+        assert_eq!(
+            TimeClue::SameWeekDayAt(Weekday::Fri, Some((0, 0, 0)), None),
+            parse_time_clue_from_str("midnight friday").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Midnight,
+            parse_time_clue_from_str("midnight tonight").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_range_ok() {
+        assert_eq!(
+            RangeClue::Last(7, Quantifier::Days),
+            parse_range_clue_from_str("last 7 days").unwrap()
+        );
+        assert_eq!(
+            RangeClue::Next(3, Quantifier::Weeks),
+            parse_range_clue_from_str("next 3 weeks").unwrap()
+        );
+        assert_eq!(
+            RangeClue::Last(1, Quantifier::Months),
+            parse_range_clue_from_str("last month").unwrap()
+        );
+        assert!(parse_range_clue_from_str("last friday").is_err());
+    }
+
+    #[test]
+    fn test_parse_fractional_ok() {
+        assert_eq!(
+            TimeClue::Relative(30, Quantifier::Min),
+            parse_time_clue_from_str("half an hour ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::RelativeFuture(15, Quantifier::Min),
+            parse_time_clue_from_str("in a quarter of an hour").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Relative(12, Quantifier::Hours),
+            parse_time_clue_from_str("half a day ago").unwrap()
+        );
+        assert_eq!(
+            (30, Quantifier::Min),
+            parse_duration_from_str("half an hour").unwrap()
+        );
+        assert_eq!(
+            (15, Quantifier::Min),
+            parse_duration_from_str("quarter of an hour").unwrap()
+        );
+        assert_eq!(
+            (2, Quantifier::Hours),
+            parse_duration_from_str("2 hours").unwrap()
+        );
+        assert!(matches!(
+            parse_time_clue_from_str("half a min ago"),
+            Err(ParseError::UnsupportedFraction(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_whole_and_fraction_ok() {
+        assert_eq!(
+            TimeClue::Relative(150, Quantifier::Min),
+            parse_time_clue_from_str("two and a half hours ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Relative(12, Quantifier::Hours),
+            parse_time_clue_from_str("half a day ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::RelativeFuture(30, Quantifier::Hours),
+            parse_time_clue_from_str("in 1 and a quarter days").unwrap()
+        );
+        assert_eq!(
+            (3, Quantifier::Hours),
+            parse_duration_from_str("three hours").unwrap()
+        );
+        assert_eq!(
+            (180, Quantifier::Months),
+            parse_duration_from_str("one and a half decades").unwrap()
+        );
+        assert!(matches!(
+            parse_time_clue_from_str("two and a half min ago"),
+            Err(ParseError::UnsupportedFraction(_))
+        ));
+        assert!(matches!(
+            parse_duration_from_str("18446744073709551615 and a half hours"),
+            Err(ParseError::DurationOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_leading_preposition_ok() {
+        for (with_preposition, bare) in [
+            ("on friday", "friday"),
+            ("by tomorrow", "tomorrow"),
+            ("on next monday at 9", "next monday at 9"),
+            ("by 5pm", "5pm"),
+            ("at 9", "9"),
+            ("at noon", "noon"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                parse_time_clue_from_str(bare).unwrap(),
+                parse_time_clue_from_str(with_preposition).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::BareInt(9),
+            parse_time_clue_from_str("at 9").unwrap()
+        );
+        assert!(parse_time_clue_from_str("onfriday").is_err());
+        assert!(parse_time_clue_from_str("on").is_err());
+    }
+
+    #[test]
+    fn test_parse_weekday_date_ok() {
+        assert_eq!(
+            TimeClue::WeekdayISO(Weekday::Fri, (2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("friday 2020-12-25").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WeekdayISO(Weekday::Thu, (2020, 12, 25), (19, 43, 0)),
+            parse_time_clue_from_str("thu, 2020-12-25T19:43").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WeekdayISO(Weekday::Fri, (2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("fri 25.12.2020").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("2020-12-25").unwrap()
+        );
+        assert!(parse_time_clue_from_str("friday 2020-360").is_err());
+    }
+
+    #[test]
+    fn test_parse_twelve_noon_midnight() {
+        assert_eq!(TimeClue::Noon, parse_time_clue_from_str("12 noon").unwrap());
+        assert_eq!(
+            TimeClue::Midnight,
+            parse_time_clue_from_str("12 midnight").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, Some((12, 0, 0)), None),
+            parse_time_clue_from_str("tomorrow at 12 noon").unwrap()
+        );
+        for s in ["5 noon", "0 midnight", "11 midnight"].iter() {
+            assert!(matches!(
+                parse_time_clue_from_str(s),
+                Err(ParseError::NoonMidnightMismatch(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_empty_input_err() {
+        for s in ["", "   ", "\t\n"].iter() {
+            assert!(matches!(
+                parse_time_clue_from_str(s),
+                Err(ParseError::EmptyInput)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_incomplete_clue_err() {
+        for (s, expected) in [
+            ("next", "next"),
+            ("last", "last"),
+            ("friday at", "friday at"),
+            ("last friday at", "last friday at"),
+            ("this", "this"),
+            ("at", "at"),
+            ("friday at  ", "friday at"),
+            ("tomorrow at", "tomorrow at"),
+            ("the 3rd at", "the 3rd at"),
+            ("2020-12-25 at", "2020-12-25 at"),
+        ]
+        .iter()
+        {
+            match parse_time_clue_from_str(s) {
+                Err(ParseError::IncompleteClue(clue)) => assert_eq!(clue, *expected),
+                other => panic!("{}: expected incomplete clue, got {:?}", s, other),
+            }
+        }
+        // junk before a dangling word is not an incomplete clue
+        for s in ["friday at foo", "blah blah at", "foo next", "19:43 at"].iter() {
+            assert!(
+                matches!(parse_time_clue_from_str(s), Err(ParseError::PestError(_))),
+                "{}",
+                s
+            );
+        }
+        assert!(parse_time_clue_from_str("last friday at 19").is_ok());
+    }
+
+    #[test]
+    fn test_parse_contradictory_direction_err() {
+        for s in [
+            "in 2 days ago",
+            "2 days in",
+            "in an hour ago",
+            "in half an hour ago",
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                parse_time_clue_from_str(s),
+                Err(ParseError::ContradictoryDirection(_))
+            ));
+        }
+        assert!(parse_time_clue_from_str("2 days ago").is_ok());
+        assert!(parse_time_clue_from_str("in 2 days").is_ok());
+    }
+
+    #[test]
+    fn test_parse_week_of_month_ok() {
+        assert_eq!(
+            TimeClue::WeekOfMonth(WeekOfMonth::Nth(2), MonthClue::Named(12)),
+            parse_time_clue_from_str("second week of december").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WeekOfMonth(WeekOfMonth::Nth(1), MonthClue::Relative(Modifier::Next)),
+            parse_time_clue_from_str("the first week of next month").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WeekOfMonth(WeekOfMonth::Last, MonthClue::This),
+            parse_time_clue_from_str("last week of this month").unwrap()
+        );
+        assert_eq!(
+            TimeClue::WeekOfMonth(WeekOfMonth::Nth(3), MonthClue::Named(5)),
+            parse_time_clue_from_str("third week of may").unwrap()
+        );
+        assert!(parse_time_clue_from_str("sixth week of may").is_err());
+    }
+
+    #[test]
+    fn test_parse_dotted_time_ok() {
+        for (dotted, colon) in [
+            ("7.30", "7:30"),
+            ("7.30pm", "7:30pm"),
+            ("19.43.20", "19:43:20"),
+            ("friday at 7.30", "friday at 7:30"),
+            ("tomorrow 8.15 am", "tomorrow 8:15 am"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                parse_time_clue_from_str(colon).unwrap(),
+                parse_time_clue_from_str(dotted).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("25.12.2020").unwrap()
+        );
+        assert!(parse_time_clue_from_str("2.5 hours ago").is_err());
+        assert!(parse_time_clue_from_str("7.").is_err());
+        // exactly 2 digits after a dot, "12.5" is a decimal quantity not 12:05
+        for s in ["12.5", "7.3", "7.300", "friday at 12.5"].iter() {
+            assert!(parse_time_clue_from_str(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_period_offset_ok() {
+        assert_eq!(
+            TimeClue::PeriodEdge(Edge::End, Period::Month),
+            parse_time_clue_from_str("end of month").unwrap()
+        );
+        assert_eq!(
+            TimeClue::PeriodEdge(Edge::Start, Period::Week),
+            parse_time_clue_from_str("the beginning of the week").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(
+                3,
+                Quantifier::Days,
+                Direction::Before,
+                Box::new(TimeClue::PeriodEdge(Edge::End, Period::Month))
+            ),
+            parse_time_clue_from_str("3 days before end of month").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(
+                1,
+                Quantifier::Weeks,
+                Direction::After,
+                Box::new(TimeClue::PeriodEdge(Edge::Start, Period::Year))
+            ),
+            parse_time_clue_from_str("a week after the start of this year").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(
+                2,
+                Quantifier::Days,
+                Direction::Before,
+                Box::new(TimeClue::ISO((2020, 12, 25), (0, 0, 0)))
+            ),
+            parse_time_clue_from_str("2 days before 2020-12-25").unwrap()
+        );
+        assert!(parse_time_clue_from_str("3 days before").is_err());
+    }
+
+    #[test]
+    fn test_parse_week_from_weekday_ok() {
+        let expected = TimeClue::Offset(
+            1,
+            Quantifier::Weeks,
+            Direction::After,
+            Box::new(TimeClue::RelativeDayAt(
+                Modifier::Next,
+                Weekday::Fri,
+                None,
+                None,
+            )),
+        );
+        for s in ["a week from friday", "a week on friday", "1 week from fri"].iter() {
+            assert_eq!(expected, parse_time_clue_from_str(s).unwrap());
+        }
+        assert!(parse_time_clue_from_str("2 weeks from friday").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        for s in [
+            "now",
+            "in 2 days",
+            "friday at 7pm",
+            "2020-12-25T19:43:00",
+            "25:00",
+        ]
+        .iter()
+        {
+            assert!(is_valid_clue(s), "{}", s);
+            assert!(validate(s).is_ok());
+        }
+        for s in [
+            "",
+            "soon",
+            "in 2 days ago",
+            "half a min ago",
+            "every monday",
+        ]
+        .iter()
+        {
+            assert!(!is_valid_clue(s), "{}", s);
+        }
+        assert!(matches!(
+            validate("in 2 days ago"),
+            Err(ParseError::ContradictoryDirection(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_date_offset_ok() {
+        let christmas = || Box::new(TimeClue::ISO((2020, 12, 25), (0, 0, 0)));
+        assert_eq!(
+            TimeClue::Offset(3, Quantifier::Days, Direction::After, christmas()),
+            parse_time_clue_from_str("2020-12-25 + 3 days").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(1, Quantifier::Weeks, Direction::Before, christmas()),
+            parse_time_clue_from_str("2020-12-25 - 1 week").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(2, Quantifier::Hours, Direction::After, christmas()),
+            parse_time_clue_from_str("25/12/2020+2h").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(
+                1,
+                Quantifier::Months,
+                Direction::After,
+                Box::new(TimeClue::ISO((2020, 12, 25), (19, 43, 0)))
+            ),
+            parse_time_clue_from_str("2020-12-25T19:43 + 1 month").unwrap()
+        );
+        assert!(parse_time_clue_from_str("2020-12-25 + ").is_err());
+    }
+
+    #[test]
+    fn test_parse_iso_signed_year_ok() {
+        assert_eq!(
+            TimeClue::ISO((1, 1, 1), (0, 0, 0)),
+            parse_time_clue_from_str("0001-01-01").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((0, 1, 1), (0, 0, 0)),
+            parse_time_clue_from_str("0000-01-01").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((-44, 3, 15), (12, 0, 0)),
+            parse_time_clue_from_str("-0044-03-15T12:00").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((10000, 1, 1), (0, 0, 0)),
+            parse_time_clue_from_str("+10000-01-01").unwrap()
+        );
+        assert_eq!(
+            TimeClue::PartialISO(-44, None),
+            parse_time_clue_from_str("-0044").unwrap()
+        );
+        // signed years require at least 4 digits, unsigned years exactly 4
+        assert!(parse_time_clue_from_str("-44-03-15").is_err());
+        assert!(parse_time_clue_from_str("10000-01-01").is_err());
+    }
+
+    #[test]
+    fn test_parse_iso_basic_ok() {
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (0, 0, 0)),
+            parse_time_clue_from_str("20201225").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (19, 43, 0)),
+            parse_time_clue_from_str("20201225T194300").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ISO((2020, 12, 25), (19, 43, 0)),
+            parse_time_clue_from_str("20201225T1943").unwrap()
+        );
+        assert_eq!(
+            parse_time_clue_from_str("2020-12-25T19:43:00").unwrap(),
+            parse_time_clue_from_str("20201225T194300").unwrap()
+        );
+        for s in [
+            "2020122",
+            "202012251",
+            "20201225T19",
+            "20201225T194",
+            "20201225T1943001",
+        ]
+        .iter()
+        {
+            assert!(parse_time_clue_from_str(s).is_err(), "{}", s);
+        }
+        // 8-digit counts are not dates
+        assert_eq!(
+            TimeClue::Relative(12345678, Quantifier::Min),
+            parse_time_clue_from_str("12345678 min ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::RelativeFuture(20201225, Quantifier::Min),
+            parse_time_clue_from_str("in 20201225 min").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_nth_weekday_ok() {
+        assert_eq!(
+            TimeClue::NthWeekday(3, Weekday::Mon, Direction::After),
+            parse_time_clue_from_str("3 mondays from now").unwrap()
+        );
+        assert_eq!(
+            TimeClue::NthWeekday(2, Weekday::Fri, Direction::After),
+            parse_time_clue_from_str("2 fridays later").unwrap()
+        );
+        assert_eq!(
+            TimeClue::NthWeekday(2, Weekday::Fri, Direction::Before),
+            parse_time_clue_from_str("2 fridays ago").unwrap()
+        );
+        assert_eq!(
+            TimeClue::NthWeekday(1, Weekday::Sun, Direction::After),
+            parse_time_clue_from_str("1 sunday from now").unwrap()
+        );
+        assert!(parse_time_clue_from_str("3 mondays").is_err());
+    }
+
+    #[test]
+    fn test_parse_this_part_of_day_ok() {
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Today, Some((15, 0, 0)), None),
+            parse_time_clue_from_str("this afternoon").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Today, Some((9, 0, 0)), None),
+            parse_time_clue_from_str("this morning").unwrap()
+        );
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Today, Some((19, 0, 0)), None),
+            parse_time_clue_from_str("this evening").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_modified_shortcut_day_err() {
+        for s in [
+            "next tomorrow",
+            "last yesterday",
+            "this today",
+            "next tomorrow at 9",
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                parse_time_clue_from_str(s),
+                Err(ParseError::ModifiedShortcutDay(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_shortcut_day_week_ok() {
+        assert_eq!(
+            TimeClue::Offset(
+                1,
+                Quantifier::Weeks,
+                Direction::After,
+                Box::new(TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, None, None))
+            ),
+            parse_time_clue_from_str("tomorrow week").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Offset(
+                1,
+                Quantifier::Weeks,
+                Direction::Before,
+                Box::new(TimeClue::ShortcutDayAt(
+                    ShortcutDay::Yesterday,
+                    Some((9, 0, 0)),
+                    Some(AMPM::PM)
+                ))
+            ),
+            parse_time_clue_from_str("yesterday week at 9pm").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_partial_iso_ok() {
+        assert_eq!(
+            TimeClue::PartialISO(2020, None),
+            parse_time_clue_from_str("2020").unwrap()
+        );
+        assert_eq!(
+            TimeClue::PartialISO(2020, Some(12)),
+            parse_time_clue_from_str("2020-12").unwrap()
+        );
+        assert_eq!(
+            RangeClue::PartialISO(2020, None),
+            parse_range_clue_from_str("2020").unwrap()
+        );
+        assert_eq!(
+            RangeClue::PartialISO(2020, Some(12)),
+            parse_range_clue_from_str("2020-12").unwrap()
+        );
+        assert_eq!(
+            TimeClue::Ordinal(2020, 360),
+            parse_time_clue_from_str("2020-360").unwrap()
+        );
+        assert!(parse_time_clue_from_str("2020-1").is_err());
+        // counts of 4 digits or more are not years
+        for (s, expected) in [
+            ("1440 min ago", TimeClue::Relative(1440, Quantifier::Min)),
+            ("1000 days ago", TimeClue::Relative(1000, Quantifier::Days)),
+            ("2020 days ago", TimeClue::Relative(2020, Quantifier::Days)),
+            ("10000 min ago", TimeClue::Relative(10000, Quantifier::Min)),
+            ("1000h ago", TimeClue::Relative(1000, Quantifier::Hours)),
+            (
+                "in 1000 days",
+                TimeClue::RelativeFuture(1000, Quantifier::Days),
+            ),
+            (
+                "in 1000h",
+                TimeClue::RelativeFuture(1000, Quantifier::Hours),
+            ),
+            (
+                "1000 hours after 2020-12-25",
+                TimeClue::Offset(
+                    1000,
+                    Quantifier::Hours,
+                    Direction::After,
+                    Box::new(TimeClue::ISO((2020, 12, 25), (0, 0, 0))),
+                ),
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(*expected, parse_time_clue_from_str(s).unwrap(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_within_range_ok() {
+        for (s, expected) in [
+            ("in the next hour", RangeClue::Next(1, Quantifier::Hours)),
+            ("in the next 3 days", RangeClue::Next(3, Quantifier::Days)),
+            ("within 2 days", RangeClue::Next(2, Quantifier::Days)),
+            ("within the hour", RangeClue::Next(1, Quantifier::Hours)),
+        ]
+        .iter()
+        {
+            assert_eq!(*expected, parse_range_clue_from_str(s).unwrap());
+        }
+        assert!(parse_range_clue_from_str("in the last hour").is_err());
+    }
+
+    #[test]
+    fn test_parse_day_of_month_ok() {
+        for (s, day) in [
+            ("1st", 1),
+            ("2nd", 2),
+            ("3rd", 3),
+            ("4th", 4),
+            ("11th", 11),
+            ("12th", 12),
+            ("13th", 13),
+            ("21st", 21),
+            ("22nd", 22),
+            ("23rd", 23),
+            ("the 31st", 31),
+            ("on the 3rd", 3),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                TimeClue::DayOfMonth(*day, None, None),
+                parse_time_clue_from_str(s).unwrap()
+            );
+        }
+        assert_eq!(
+            TimeClue::DayOfMonth(22, Some((5, 0, 0)), Some(AMPM::PM)),
+            parse_time_clue_from_str("the 22nd at 5pm").unwrap()
+        );
+        for s in ["3th", "1nd", "11st", "12nd", "22th"].iter() {
+            assert!(matches!(
+                parse_time_clue_from_str(s),
+                Err(ParseError::OrdinalSuffixMismatch(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_parse_oclock_ok() {
+        for (s, expected) in [
+            ("7 o'clock", TimeClue::Time((7, 0, 0), None)),
+            ("7 o’clock", TimeClue::Time((7, 0, 0), None)),
+            ("7 o'clock pm", TimeClue::Time((7, 0, 0), Some(AMPM::PM))),
+            (
+                "7 o'clock in the evening",
+                TimeClue::Time((7, 0, 0), Some(AMPM::PM)),
+            ),
+            (
+                "7 in the morning",
+                TimeClue::Time((7, 0, 0), Some(AMPM::AM)),
+            ),
+            (
+                "10:30 at night",
+                TimeClue::Time((10, 30, 0), Some(AMPM::PM)),
+            ),
+            ("12 at night", TimeClue::Midnight),
+            (
+                "12:30 at night",
+                TimeClue::Time((12, 30, 0), Some(AMPM::AM)),
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(*expected, parse_time_clue_from_str(s).unwrap());
+        }
+        assert_eq!(
+            TimeClue::ShortcutDayAt(ShortcutDay::Tomorrow, Some((7, 0, 0)), Some(AMPM::PM)),
+            parse_time_clue_from_str("tomorrow at 7 o'clock in the evening").unwrap()
+        );
+        assert_eq!(
+            (7, 0, 0, Some(AMPM::PM)),
+            parse_time_only_from_str("friday at 7 o'clock in the evening").unwrap()
+        );
+        assert!(parse_time_clue_from_str("7:30 o'clock").is_err());
     }
 }
@@ -0,0 +1,392 @@
+//! Flat representation of `TimeClue` for FFI or WASM bindings.
+//!
+//! `TimeClueRepr` is a `#[repr(C)]` struct: `tag` identifies the `TimeClue` variant
+//! (same tags as the `binary` representation) and only the fields used by this variant are set,
+//! other fields keep their default value.
+//!
+//! Enums without fields are encoded as bytes, same as in the `binary` representation
+//! e.g. `weekday` is 0 for monday... 6 for sunday.
+
+use crate::binary::{
+    am_or_pm_from_tag, am_or_pm_tag, direction_from_tag, direction_tag, edge_from_tag, edge_tag,
+    modifier_from_tag, modifier_tag, period_from_tag, period_tag, quantifier_from_tag,
+    quantifier_tag, shortcut_day_from_tag, shortcut_day_tag, weekday_from_tag, weekday_tag,
+    DecodeError,
+};
+use crate::parser::{MonthClue, TimeClue, WeekOfMonth, AMPM, HMS, YMD};
+use alloc::boxed::Box;
+use chrono::FixedOffset;
+use core::convert::TryFrom;
+
+/// Flat, tagged representation of a `TimeClue`, see the `repr` module.
+#[repr(C)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeClueRepr {
+    /// `TimeClue` variant, see the `binary` module.
+    pub tag: u8,
+    pub weekday: u8,
+    pub modifier: u8,
+    pub shortcut_day: u8,
+    pub quantifier: u8,
+    pub direction: u8,
+    pub edge: u8,
+    pub period: u8,
+    /// 0 for none, 1 for am, 2 for pm
+    pub am_or_pm: u8,
+    /// Whether `hour`, `minute`, `second` are set, for optional times.
+    pub has_time: bool,
+    /// Whether `month` is set, for `TimeClue::PartialISO`.
+    pub has_month: bool,
+    /// 0 for `WeekOfMonth::Nth(week)`, 1 for `WeekOfMonth::Last`.
+    pub week_kind: u8,
+    /// 0 for `MonthClue::Named(month)`, 1 for `MonthClue::This`, 2 for `MonthClue::Relative(modifier)`.
+    pub month_kind: u8,
+    /// Count of `quantifier`, of `weekday` for `TimeClue::NthWeekday`,
+    /// or the integer of `TimeClue::BareInt`.
+    pub n: u64,
+    pub week: u32,
+    pub year: i32,
+    pub month: u32,
+    /// Day of month, or day of year for `TimeClue::Ordinal`.
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+    /// Offset east of UTC in seconds, for `TimeClue::WithOffset`.
+    pub offset_seconds: i32,
+    /// Wrapped time clue, for `TimeClue::Offset` and `TimeClue::WithOffset`.
+    pub inner: Option<Box<TimeClueRepr>>,
+}
+
+impl TimeClueRepr {
+    fn with_ymd(mut self, (year, month, day): YMD) -> Self {
+        self.year = year;
+        self.month = month;
+        self.day = day;
+        self
+    }
+
+    fn with_hms(mut self, (hour, minute, second): HMS) -> Self {
+        self.has_time = true;
+        self.hour = hour;
+        self.minute = minute;
+        self.second = second;
+        self
+    }
+
+    fn with_time(self, hms: Option<HMS>, am_or_pm: Option<AMPM>) -> Self {
+        let mut repr = match hms {
+            Some(hms) => self.with_hms(hms),
+            None => self,
+        };
+        repr.am_or_pm = am_or_pm.map_or(0, |am_or_pm| am_or_pm_tag(&am_or_pm) + 1);
+        repr
+    }
+
+    fn ymd(&self) -> YMD {
+        (self.year, self.month, self.day)
+    }
+
+    fn hms(&self) -> HMS {
+        (self.hour, self.minute, self.second)
+    }
+
+    fn optional_hms(&self) -> Option<HMS> {
+        if self.has_time {
+            Some(self.hms())
+        } else {
+            None
+        }
+    }
+
+    fn optional_am_or_pm(&self) -> Result<Option<AMPM>, DecodeError> {
+        match self.am_or_pm {
+            0 => Ok(None),
+            tag => am_or_pm_from_tag(tag - 1).map(Some),
+        }
+    }
+
+    fn n(&self) -> Result<usize, DecodeError> {
+        usize::try_from(self.n).map_err(|_| DecodeError::InvalidValue)
+    }
+
+    fn inner(self) -> Result<Box<TimeClue>, DecodeError> {
+        match self.inner {
+            Some(inner) => Ok(Box::new(TimeClue::try_from(*inner)?)),
+            None => Err(DecodeError::InvalidValue),
+        }
+    }
+}
+
+impl From<TimeClue> for TimeClueRepr {
+    fn from(time_clue: TimeClue) -> Self {
+        let repr = |tag| TimeClueRepr {
+            tag,
+            ..TimeClueRepr::default()
+        };
+        match time_clue {
+            TimeClue::Now => repr(0),
+            TimeClue::Time(hms, am_or_pm) => repr(1).with_time(Some(hms), am_or_pm),
+            TimeClue::Midnight => repr(2),
+            TimeClue::Relative(n, quantifier) => TimeClueRepr {
+                n: n as u64,
+                quantifier: quantifier_tag(&quantifier),
+                ..repr(3)
+            },
+            TimeClue::RelativeDayAt(modifier, weekday, hms, am_or_pm) => TimeClueRepr {
+                modifier: modifier_tag(&modifier),
+                weekday: weekday_tag(&weekday),
+                ..repr(4)
+            }
+            .with_time(hms, am_or_pm),
+            TimeClue::RelativeFuture(n, quantifier) => TimeClueRepr {
+                n: n as u64,
+                quantifier: quantifier_tag(&quantifier),
+                ..repr(5)
+            },
+            TimeClue::SameWeekDayAt(weekday, hms, am_or_pm) => TimeClueRepr {
+                weekday: weekday_tag(&weekday),
+                ..repr(6)
+            }
+            .with_time(hms, am_or_pm),
+            TimeClue::ShortcutDayAt(shortcut_day, hms, am_or_pm) => TimeClueRepr {
+                shortcut_day: shortcut_day_tag(&shortcut_day),
+                ..repr(7)
+            }
+            .with_time(hms, am_or_pm),
+            TimeClue::ISO(ymd, hms) => repr(8).with_ymd(ymd).with_hms(hms),
+            TimeClue::WeekdayISO(weekday, ymd, hms) => TimeClueRepr {
+                weekday: weekday_tag(&weekday),
+                ..repr(9)
+            }
+            .with_ymd(ymd)
+            .with_hms(hms),
+            TimeClue::NthWeekday(n, weekday, direction) => TimeClueRepr {
+                n: n as u64,
+                weekday: weekday_tag(&weekday),
+                direction: direction_tag(&direction),
+                ..repr(10)
+            },
+            TimeClue::WeekOfMonth(week, month) => {
+                let mut repr = repr(11);
+                match week {
+                    WeekOfMonth::Nth(n) => repr.week = n,
+                    WeekOfMonth::Last => repr.week_kind = 1,
+                }
+                match month {
+                    MonthClue::Named(m) => repr.month = m,
+                    MonthClue::This => repr.month_kind = 1,
+                    MonthClue::Relative(modifier) => {
+                        repr.month_kind = 2;
+                        repr.modifier = modifier_tag(&modifier);
+                    }
+                }
+                repr
+            }
+            TimeClue::PeriodEdge(edge, period) => TimeClueRepr {
+                edge: edge_tag(&edge),
+                period: period_tag(&period),
+                ..repr(12)
+            },
+            TimeClue::Offset(n, quantifier, direction, time_clue) => TimeClueRepr {
+                n: n as u64,
+                quantifier: quantifier_tag(&quantifier),
+                direction: direction_tag(&direction),
+                inner: Some(Box::new(TimeClueRepr::from(*time_clue))),
+                ..repr(13)
+            },
+            TimeClue::PartialISO(year, month) => TimeClueRepr {
+                year,
+                has_month: month.is_some(),
+                month: month.unwrap_or_default(),
+                ..repr(14)
+            },
+            TimeClue::Ordinal(year, day) => TimeClueRepr {
+                year,
+                day,
+                ..repr(15)
+            },
+            TimeClue::WithOffset(time_clue, offset) => TimeClueRepr {
+                offset_seconds: offset.local_minus_utc(),
+                inner: Some(Box::new(TimeClueRepr::from(*time_clue))),
+                ..repr(16)
+            },
+            TimeClue::DayOfMonth(day, hms, am_or_pm) => {
+                TimeClueRepr { day, ..repr(17) }.with_time(hms, am_or_pm)
+            }
+            TimeClue::Noon => repr(18),
+            TimeClue::BareInt(n) => TimeClueRepr {
+                n: n as u64,
+                ..repr(19)
+            },
+        }
+    }
+}
+
+impl TryFrom<TimeClueRepr> for TimeClue {
+    type Error = DecodeError;
+
+    fn try_from(repr: TimeClueRepr) -> Result<Self, Self::Error> {
+        match repr.tag {
+            0 => Ok(TimeClue::Now),
+            1 => Ok(TimeClue::Time(repr.hms(), repr.optional_am_or_pm()?)),
+            2 => Ok(TimeClue::Midnight),
+            3 => Ok(TimeClue::Relative(
+                repr.n()?,
+                quantifier_from_tag(repr.quantifier)?,
+            )),
+            4 => Ok(TimeClue::RelativeDayAt(
+                modifier_from_tag(repr.modifier)?,
+                weekday_from_tag(repr.weekday)?,
+                repr.optional_hms(),
+                repr.optional_am_or_pm()?,
+            )),
+            5 => Ok(TimeClue::RelativeFuture(
+                repr.n()?,
+                quantifier_from_tag(repr.quantifier)?,
+            )),
+            6 => Ok(TimeClue::SameWeekDayAt(
+                weekday_from_tag(repr.weekday)?,
+                repr.optional_hms(),
+                repr.optional_am_or_pm()?,
+            )),
+            7 => Ok(TimeClue::ShortcutDayAt(
+                shortcut_day_from_tag(repr.shortcut_day)?,
+                repr.optional_hms(),
+                repr.optional_am_or_pm()?,
+            )),
+            8 => Ok(TimeClue::ISO(repr.ymd(), repr.hms())),
+            9 => Ok(TimeClue::WeekdayISO(
+                weekday_from_tag(repr.weekday)?,
+                repr.ymd(),
+                repr.hms(),
+            )),
+            10 => Ok(TimeClue::NthWeekday(
+                repr.n()?,
+                weekday_from_tag(repr.weekday)?,
+                direction_from_tag(repr.direction)?,
+            )),
+            11 => {
+                let week = match repr.week_kind {
+                    0 => WeekOfMonth::Nth(repr.week),
+                    1 => WeekOfMonth::Last,
+                    tag => return Err(DecodeError::InvalidTag(tag)),
+                };
+                let month = match repr.month_kind {
+                    0 => MonthClue::Named(repr.month),
+                    1 => MonthClue::This,
+                    2 => MonthClue::Relative(modifier_from_tag(repr.modifier)?),
+                    tag => return Err(DecodeError::InvalidTag(tag)),
+                };
+                Ok(TimeClue::WeekOfMonth(week, month))
+            }
+            12 => Ok(TimeClue::PeriodEdge(
+                edge_from_tag(repr.edge)?,
+                period_from_tag(repr.period)?,
+            )),
+            13 => Ok(TimeClue::Offset(
+                repr.n()?,
+                quantifier_from_tag(repr.quantifier)?,
+                direction_from_tag(repr.direction)?,
+                repr.inner()?,
+            )),
+            14 => Ok(TimeClue::PartialISO(
+                repr.year,
+                if repr.has_month {
+                    Some(repr.month)
+                } else {
+                    None
+                },
+            )),
+            15 => Ok(TimeClue::Ordinal(repr.year, repr.day)),
+            16 => {
+                let offset =
+                    FixedOffset::east_opt(repr.offset_seconds).ok_or(DecodeError::InvalidValue)?;
+                Ok(TimeClue::WithOffset(repr.inner()?, offset))
+            }
+            17 => Ok(TimeClue::DayOfMonth(
+                repr.day,
+                repr.optional_hms(),
+                repr.optional_am_or_pm()?,
+            )),
+            18 => Ok(TimeClue::Noon),
+            19 => Ok(TimeClue::BareInt(
+                u32::try_from(repr.n).map_err(|_| DecodeError::InvalidValue)?,
+            )),
+            tag => Err(DecodeError::InvalidTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::binary::DecodeError;
+    use crate::parser::{parse_time_clue_from_str, TimeClue};
+    use crate::repr::TimeClueRepr;
+    use core::convert::TryFrom;
+
+    #[test]
+    fn test_round_trip() {
+        // one time clue per variant
+        for s in [
+            "now",
+            "7:30pm",
+            "midnight",
+            "noon",
+            "15",
+            "4 min ago",
+            "last friday at 19:43",
+            "in 2 weeks",
+            "monday at 6 am",
+            "tomorrow at 9",
+            "yesterday",
+            "2020-12-25T19:43:00",
+            "friday 2020-12-25",
+            "3 mondays from now",
+            "second week of next month",
+            "last week of december",
+            "3 days before end of month",
+            "2020-12",
+            "2020",
+            "2020-360",
+            "tomorrow at 9 -0800",
+            "the 22nd at 5pm",
+        ]
+        .iter()
+        {
+            let time_clue = parse_time_clue_from_str(s).unwrap();
+            let repr = TimeClueRepr::from(time_clue.clone());
+            assert_eq!(repr.tag, time_clue.to_bytes()[1], "{}", s);
+            assert_eq!(TimeClue::try_from(repr).unwrap(), time_clue, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_try_from_err() {
+        let invalid_tag = TimeClueRepr {
+            tag: 255,
+            ..TimeClueRepr::default()
+        };
+        assert_eq!(
+            TimeClue::try_from(invalid_tag),
+            Err(DecodeError::InvalidTag(255))
+        );
+        let missing_inner = TimeClueRepr {
+            tag: 13,
+            ..TimeClueRepr::default()
+        };
+        assert_eq!(
+            TimeClue::try_from(missing_inner),
+            Err(DecodeError::InvalidValue)
+        );
+        let invalid_weekday = TimeClueRepr {
+            tag: 6,
+            weekday: 7,
+            ..TimeClueRepr::default()
+        };
+        assert_eq!(
+            TimeClue::try_from(invalid_weekday),
+            Err(DecodeError::InvalidTag(7))
+        );
+    }
+}